@@ -1,7 +1,8 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, MolochV2Config, TokensConfig, Balance, WASM_BINARY, Signature,
+	NATIVE_CURRENCY_ID, USD_CURRENCY_ID,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -13,6 +14,9 @@ use sp_chain_spec::Properties;
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+// 1 DORA, the token has 12 decimals
+const DORA: Balance = 1_000_000_000_000;
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
@@ -72,6 +76,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie"),
 			],
+			// Moloch summoners
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), 1, 0),
+			],
 			true,
 		),
 		// Bootnodes
@@ -120,6 +128,11 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Moloch summoners
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), 1, 0),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), 1, 0),
+			],
 			true,
 		),
 		// Bootnodes
@@ -141,6 +154,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	moloch_summoners: Vec<(AccountId, u128, u128)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -153,6 +167,10 @@ fn testnet_genesis(
 			// Configure endowed accounts with initial balance of 1 << 60.
 			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
 		}),
+		orml_tokens: Some(TokensConfig {
+			// Endow the same accounts with the stablecoin.
+			endowed_accounts: endowed_accounts.iter().cloned().map(|k|(k, USD_CURRENCY_ID, 1 << 60)).collect(),
+		}),
 		pallet_aura: Some(AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
 		}),
@@ -163,5 +181,18 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_moloch_v2: Some(MolochV2Config {
+			// Summon the first DAO, so that proposals can be submitted right away.
+			summoners: moloch_summoners,
+			// deposits are paid in the native token
+			approved_tokens: vec![NATIVE_CURRENCY_ID, USD_CURRENCY_ID],
+			// in seconds
+			period_duration: 120,
+			voting_period_length: 5,
+			grace_period_length: 3,
+			dilution_bound: 3,
+			proposal_deposit: 10 * DORA,
+			processing_reward: DORA,
+		}),
 	}
 }
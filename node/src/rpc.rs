@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index, Hash};
+use node_template_runtime::{opaque::Block, AccountId, Balance, Index, Hash, CurrencyId};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_block_builder::BlockBuilder;
@@ -35,12 +35,14 @@ pub fn create_full<C, P>(
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: pallet_quadratic_funding_rpc::QuadraticFundingRuntimeApi<Block, AccountId, Hash>,
+	C::Api: pallet_moloch_v2_rpc::MolochV2RuntimeApi<Block, AccountId, CurrencyId>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use pallet_quadratic_funding_rpc::{QuadraticFunding, QuadraticFundingApi};
+	use pallet_moloch_v2_rpc::{MolochV2, MolochV2Api};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
@@ -64,5 +66,9 @@ pub fn create_full<C, P>(
 		QuadraticFundingApi::to_delegate(QuadraticFunding::new(client.clone()))
 	);
 
+	io.extend_with(
+		MolochV2Api::to_delegate(MolochV2::new(client.clone()))
+	);
+
 	io
 }
@@ -0,0 +1,335 @@
+use std::sync::Arc;
+use codec::Codec;
+use serde::{Serialize, Deserialize};
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, MaybeDisplay}};
+use sp_api::ProvideRuntimeApi;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use pallet_moloch_v2_runtime_api::{DaoId, Member, Proposal, ProposalKind, ProposalStatus, VotingMode, FailureReason, ProcessPreview};
+pub use pallet_moloch_v2_runtime_api::MolochV2Api as MolochV2RuntimeApi;
+pub use self::gen_client::Client as MolochV2Client;
+
+/// A member of a DAO, amounts are u128 so they are returned as NumberOrHex
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberInfo<AccountId> {
+	pub delegate_key: AccountId,
+	pub shares: NumberOrHex,
+	pub loot: NumberOrHex,
+	pub highest_index_yes_vote: NumberOrHex,
+	pub jailed_at: NumberOrHex,
+}
+
+impl<AccountId> From<Member<AccountId>> for MemberInfo<AccountId> {
+	fn from(member: Member<AccountId>) -> Self {
+		MemberInfo {
+			delegate_key: member.delegate_key,
+			shares: to_number(member.shares),
+			loot: to_number(member.loot),
+			highest_index_yes_vote: to_number(member.highest_index_yes_vote),
+			jailed_at: to_number(member.jailed_at),
+		}
+	}
+}
+
+/// A proposal with its status derived from the current period
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalInfo<AccountId, CurrencyId> {
+	pub proposal_id: NumberOrHex,
+	pub proposer: AccountId,
+	pub applicant: AccountId,
+	pub sponsor: AccountId,
+	pub shares_requested: NumberOrHex,
+	pub loot_requested: NumberOrHex,
+	pub payment_requested: NumberOrHex,
+	pub payment_token: CurrencyId,
+	pub tribute_offered: NumberOrHex,
+	pub tribute_token: CurrencyId,
+	pub kind: ProposalKind,
+	pub status: ProposalStatus,
+	pub starting_period: NumberOrHex,
+	pub voting_period_length: NumberOrHex,
+	pub grace_period_length: NumberOrHex,
+	pub voting_mode: VotingMode,
+	pub yes_votes: NumberOrHex,
+	pub no_votes: NumberOrHex,
+	pub yes_shares: NumberOrHex,
+	pub no_shares: NumberOrHex,
+	pub details: Bytes,
+}
+
+impl<AccountId, CurrencyId> From<(u128, Proposal<AccountId, CurrencyId>, ProposalStatus)> for ProposalInfo<AccountId, CurrencyId> {
+	fn from((proposal_id, proposal, status): (u128, Proposal<AccountId, CurrencyId>, ProposalStatus)) -> Self {
+		ProposalInfo {
+			proposal_id: to_number(proposal_id),
+			proposer: proposal.proposer,
+			applicant: proposal.applicant,
+			sponsor: proposal.sponsor,
+			shares_requested: to_number(proposal.shares_requested),
+			loot_requested: to_number(proposal.loot_requested),
+			payment_requested: to_number(proposal.payment_requested),
+			payment_token: proposal.payment_token,
+			tribute_offered: to_number(proposal.tribute_offered),
+			tribute_token: proposal.tribute_token,
+			kind: proposal.kind,
+			status: status,
+			starting_period: to_number(proposal.starting_period),
+			voting_period_length: to_number(proposal.voting_period_length),
+			grace_period_length: to_number(proposal.grace_period_length),
+			voting_mode: proposal.voting_mode,
+			yes_votes: to_number(proposal.yes_votes),
+			no_votes: to_number(proposal.no_votes),
+			yes_shares: to_number(proposal.yes_shares),
+			no_shares: to_number(proposal.no_shares),
+			details: proposal.details.into(),
+		}
+	}
+}
+
+/// What processing a proposal would do
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessPreviewInfo<CurrencyId> {
+	pub passed: bool,
+	pub failure: Option<FailureReason>,
+	pub reward_token: CurrencyId,
+	pub reward: NumberOrHex,
+}
+
+impl<CurrencyId> From<ProcessPreview<CurrencyId>> for ProcessPreviewInfo<CurrencyId> {
+	fn from(preview: ProcessPreview<CurrencyId>) -> Self {
+		ProcessPreviewInfo {
+			passed: preview.passed,
+			failure: preview.failure,
+			reward_token: preview.reward_token,
+			reward: to_number(preview.reward),
+		}
+	}
+}
+
+// serde_json can not handle u128, see https://github.com/paritytech/substrate/issues/4641
+fn to_number(value: u128) -> NumberOrHex {
+	if value <= u64::max_value() as u128 {
+		NumberOrHex::Number(value as u64)
+	} else {
+		NumberOrHex::Hex(value.into())
+	}
+}
+
+#[rpc]
+pub trait MolochV2Api<AccountId, CurrencyId> {
+	#[rpc(name = "moloch_member")]
+	fn member(&self, dao_id: DaoId, who: AccountId) -> Result<Option<MemberInfo<AccountId>>>;
+
+	/// At most `count` proposals starting from proposal id `start`
+	#[rpc(name = "moloch_proposals")]
+	fn proposals(&self, dao_id: DaoId, start: u64, count: u32) -> Result<Vec<ProposalInfo<AccountId, CurrencyId>>>;
+
+	/// Proposal ids in queue order, the queue index is used to vote and process
+	#[rpc(name = "moloch_proposalQueue")]
+	fn proposal_queue(&self, dao_id: DaoId) -> Result<Vec<NumberOrHex>>;
+
+	#[rpc(name = "moloch_currentPeriod")]
+	fn current_period(&self, dao_id: DaoId) -> Result<Option<NumberOrHex>>;
+
+	/// 1 for yes, 2 for no, 3 to abstain
+	#[rpc(name = "moloch_memberVote")]
+	fn member_vote(&self, dao_id: DaoId, proposal_id: u64, who: AccountId) -> Result<Option<u8>>;
+
+	#[rpc(name = "moloch_guildBank")]
+	fn guild_bank_balances(&self, dao_id: DaoId) -> Result<Vec<(CurrencyId, NumberOrHex)>>;
+
+	/// Internal balances which can be withdrawn
+	#[rpc(name = "moloch_userTokenBalances")]
+	fn user_token_balances(&self, dao_id: DaoId, who: AccountId) -> Result<Vec<(CurrencyId, NumberOrHex)>>;
+
+	/// Tokens credited by `rage_quit` with the same arguments
+	#[rpc(name = "moloch_previewRagequit")]
+	fn preview_ragequit(&self, dao_id: DaoId, who: AccountId, shares_to_burn: u64, loot_to_burn: u64) -> Result<Vec<(CurrencyId, NumberOrHex)>>;
+
+	/// Why a proposal would fail with its current votes, null if it would pass
+	#[rpc(name = "moloch_previewOutcome")]
+	fn preview_outcome(&self, dao_id: DaoId, proposal_id: u64) -> Result<Option<FailureReason>>;
+
+	/// Outcome and processing reward of processing the proposal at `proposal_index` of the queue
+	#[rpc(name = "moloch_previewProcess")]
+	fn preview_process(&self, dao_id: DaoId, proposal_index: u64) -> Result<ProcessPreviewInfo<CurrencyId>>;
+
+	/// Shares of a member when `period` starts, the voting power on proposals whose starting period it is
+	#[rpc(name = "moloch_sharesAt")]
+	fn shares_at(&self, dao_id: DaoId, who: AccountId, period: u64) -> Result<NumberOrHex>;
+
+	#[rpc(name = "moloch_totalSharesAt")]
+	fn total_shares_at(&self, dao_id: DaoId, period: u64) -> Result<NumberOrHex>;
+
+	/// The nonce the next vote signed by a delegate key for `submit_votes_signed` has to carry
+	#[rpc(name = "moloch_voteNonce")]
+	fn vote_nonce(&self, dao_id: DaoId, who: AccountId) -> Result<u64>;
+}
+
+/// A struct that implements the [`MolochV2Api`].
+pub struct MolochV2<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> MolochV2<C, P> {
+	/// Create new `MolochV2` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The transaction was not decodable.
+	DecodeError,
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The extrinsic being previewed would fail.
+	Rejected,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::DecodeError => 2,
+			Error::Rejected => 3,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query moloch state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn rejected(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::Rejected.into()),
+		message: "The extrinsic would fail.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, CurrencyId> MolochV2Api<
+AccountId,
+CurrencyId,
+> for MolochV2<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: MolochV2RuntimeApi<Block, AccountId, CurrencyId>,
+	AccountId: Clone + MaybeDisplay + Codec,
+	CurrencyId: Codec,
+{
+	fn member(&self, dao_id: DaoId, who: AccountId) -> Result<Option<MemberInfo<AccountId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.member(&at, dao_id, who)
+			.map(|member| member.map(Into::into))
+			.map_err(runtime_error)
+	}
+
+	fn proposals(&self, dao_id: DaoId, start: u64, count: u32) -> Result<Vec<ProposalInfo<AccountId, CurrencyId>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.proposals(&at, dao_id, start.into(), count)
+			.map(|proposals| proposals.into_iter().map(Into::into).collect())
+			.map_err(runtime_error)
+	}
+
+	fn proposal_queue(&self, dao_id: DaoId) -> Result<Vec<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.proposal_queue(&at, dao_id)
+			.map(|queue| queue.into_iter().map(to_number).collect())
+			.map_err(runtime_error)
+	}
+
+	fn current_period(&self, dao_id: DaoId) -> Result<Option<NumberOrHex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.current_period(&at, dao_id)
+			.map(|period| period.map(to_number))
+			.map_err(runtime_error)
+	}
+
+	fn member_vote(&self, dao_id: DaoId, proposal_id: u64, who: AccountId) -> Result<Option<u8>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.member_vote(&at, dao_id, proposal_id.into(), who).map_err(runtime_error)
+	}
+
+	fn guild_bank_balances(&self, dao_id: DaoId) -> Result<Vec<(CurrencyId, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.guild_bank_balances(&at, dao_id)
+			.map(|balances| balances.into_iter().map(|(token, balance)| (token, to_number(balance))).collect())
+			.map_err(runtime_error)
+	}
+
+	fn user_token_balances(&self, dao_id: DaoId, who: AccountId) -> Result<Vec<(CurrencyId, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.user_token_balances(&at, dao_id, who)
+			.map(|balances| balances.into_iter().map(|(token, balance)| (token, to_number(balance))).collect())
+			.map_err(runtime_error)
+	}
+
+	fn preview_ragequit(&self, dao_id: DaoId, who: AccountId, shares_to_burn: u64, loot_to_burn: u64) -> Result<Vec<(CurrencyId, NumberOrHex)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.preview_ragequit(&at, dao_id, who, shares_to_burn.into(), loot_to_burn.into())
+			.map_err(runtime_error)?
+			.map(|payout| payout.into_iter().map(|(token, balance)| (token, to_number(balance))).collect())
+			.map_err(rejected)
+	}
+
+	fn preview_outcome(&self, dao_id: DaoId, proposal_id: u64) -> Result<Option<FailureReason>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.preview_outcome(&at, dao_id, proposal_id.into())
+			.map_err(runtime_error)?
+			.map_err(rejected)
+	}
+
+	fn preview_process(&self, dao_id: DaoId, proposal_index: u64) -> Result<ProcessPreviewInfo<CurrencyId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.preview_process(&at, dao_id, proposal_index.into())
+			.map_err(runtime_error)?
+			.map(Into::into)
+			.map_err(rejected)
+	}
+
+	fn shares_at(&self, dao_id: DaoId, who: AccountId, period: u64) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.shares_at(&at, dao_id, who, period.into())
+			.map(to_number)
+			.map_err(runtime_error)
+	}
+
+	fn total_shares_at(&self, dao_id: DaoId, period: u64) -> Result<NumberOrHex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.total_shares_at(&at, dao_id, period.into())
+			.map(to_number)
+			.map_err(runtime_error)
+	}
+
+	fn vote_nonce(&self, dao_id: DaoId, who: AccountId) -> Result<u64> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+		api.vote_nonce(&at, dao_id, who).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,45 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Here we declare the runtime API. It is implemented it the `impl` block in
+// runtime amalgamator file (the `runtime/src/lib.rs`)
+use codec::{Codec, Encode};
+use sp_runtime::{DispatchError, traits::MaybeDisplay};
+use sp_std::vec::Vec;
+pub use pallet_moloch_v2::{DaoId, Member, Proposal, ProposalKind, ProposalStatus, VotingMode, FailureReason, ProcessPreview};
+
+sp_api::decl_runtime_apis! {
+	pub trait MolochV2Api<AccountId, CurrencyId> where
+	AccountId: Clone + MaybeDisplay + Encode,
+	CurrencyId: Codec
+	{
+		// internal balance of a token which can be withdrawn
+		fn user_token_balance(dao_id: DaoId, who: AccountId, token: CurrencyId) -> u128;
+		// all the internal balances of an account, (token, balance)
+		fn user_token_balances(dao_id: DaoId, who: AccountId) -> Vec<(CurrencyId, u128)>;
+		// member info, None if not a member
+		fn member(dao_id: DaoId, who: AccountId) -> Option<Member<AccountId>>;
+		// at most `count` proposals from id `start`, (proposal_id, proposal, status)
+		fn proposals(dao_id: DaoId, start: u128, count: u32) -> Vec<(u128, Proposal<AccountId, CurrencyId>, ProposalStatus)>;
+		// proposal ids in sponsored order, the index in the queue is used for voting and processing
+		fn proposal_queue(dao_id: DaoId) -> Vec<u128>;
+		// None if the DAO does not exist
+		fn current_period(dao_id: DaoId) -> Option<u128>;
+		// 1 for yes, 2 for no, 3 to abstain, None if not voted
+		fn member_vote(dao_id: DaoId, proposal_id: u128, who: AccountId) -> Option<u8>;
+		// guild bank balance of each whitelisted token, (token, balance)
+		fn guild_bank_balances(dao_id: DaoId) -> Vec<(CurrencyId, u128)>;
+		// the previews run the same checks as the extrinsics without writing anything
+		// tokens credited for burning the shares and loot, (token, balance)
+		fn preview_ragequit(dao_id: DaoId, who: AccountId, shares_to_burn: u128, loot_to_burn: u128) -> Result<Vec<(CurrencyId, u128)>, DispatchError>;
+		// why a proposal would fail with its current votes, None if it would pass
+		fn preview_outcome(dao_id: DaoId, proposal_id: u128) -> Result<Option<FailureReason>, DispatchError>;
+		// outcome and processing reward of the proposal at `proposal_index` of the queue
+		fn preview_process(dao_id: DaoId, proposal_index: u128) -> Result<ProcessPreview<CurrencyId>, DispatchError>;
+		// shares of a member when a period starts, its voting power on proposals starting in that period
+		fn shares_at(dao_id: DaoId, who: AccountId, period: u128) -> u128;
+		// total shares of the DAO when a period starts
+		fn total_shares_at(dao_id: DaoId, period: u128) -> u128;
+		// the nonce the next signed vote of a delegate key has to carry
+		fn vote_nonce(dao_id: DaoId, who: AccountId) -> u64;
+	}
+}
@@ -0,0 +1,888 @@
+//! Benchmarks for pallet_moloch_v2
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+// benchmarks! expects the pallet trait to be named `Trait`
+use crate::Config as Trait;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::{KeyTypeId, traits::StaticLookup};
+use sp_std::{prelude::*, vec};
+
+const SEED: u32 = 0;
+const MAX_SUMMONERS: u32 = 100;
+const MAX_QUEUE: u32 = 100;
+// period_duration of the benchmarked DAO, in the unit of the runtime's clock
+const PERIOD_DURATION: u32 = 10;
+// keys signing the relayed votes
+const VOTE_KEY_TYPE: KeyTypeId = KeyTypeId(*b"mlch");
+
+/// The benchmarks expect token ids which decode from a u32 index, like the runtime's `CurrencyId`
+fn token<T: Config>(index: u32) -> T::CurrencyId {
+	T::CurrencyId::decode(&mut &index.encode()[..]).unwrap_or_default()
+}
+
+fn tokens<T: Config>(count: u32) -> Vec<T::CurrencyId> {
+	(0..count).map(|i| token::<T>(i)).collect()
+}
+
+fn fund<T: Config>(who: &T::AccountId, tokens: &[T::CurrencyId]) {
+	for token in tokens.iter() {
+		let _ = T::Currency::deposit(*token, who, Module::<T>::u128_to_balance(1_000_000_000_000));
+	}
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, SEED);
+	fund::<T>(&who, &tokens::<T>(1));
+	who
+}
+
+/// Summon a DAO with `summoner` as the only member and `t` whitelisted tokens
+fn summon_dao<T: Config>(summoner: &T::AccountId, t: u32) -> DaoId {
+	let approved_tokens = tokens::<T>(t);
+	fund::<T>(summoner, &approved_tokens);
+	Module::<T>::summon(
+		RawOrigin::Signed(summoner.clone()).into(),
+		vec![(summoner.clone(), 1, 0)],
+		approved_tokens,
+		Module::<T>::u128_to_balance(1_000_000),
+		PERIOD_DURATION,
+		2,
+		2,
+		1,
+		Module::<T>::u128_to_balance(100),
+		Module::<T>::u128_to_balance(50),
+	).expect("summon should work");
+	DaoCount::get() - 1
+}
+
+/// A transfer of the native token from the action account, which the runtime lets action proposals dispatch
+fn transfer_call<T: Config + pallet_balances::Trait>(amount: u32) -> <T as Config>::Call
+	where <T as Config>::Call: From<pallet_balances::Call<T>>
+{
+	let recipient: T::AccountId = account("recipient", 0, SEED);
+	pallet_balances::Call::<T>::transfer(T::Lookup::unlookup(recipient), amount.into()).into()
+}
+
+/// Move the clock to the given period of a DAO
+fn set_period<T: Config>(dao_id: DaoId, period: u128) {
+	T::Clock::set_now(SummonTime::get(dao_id) + period * PERIOD_DURATION as u128);
+}
+
+/// Submit a standard proposal and sponsor it, returns the proposal id
+fn sponsored_proposal<T: Config>(dao_id: DaoId, sponsor: &T::AccountId, index: u32) -> u128 {
+	let proposer = funded_account::<T>("proposer", index);
+	let deposit_token = Module::<T>::deposit_token(dao_id);
+	Module::<T>::submit_proposal(
+		RawOrigin::Signed(proposer.clone()).into(),
+		dao_id,
+		proposer,
+		Module::<T>::u128_to_balance(100),
+		deposit_token,
+		1,
+		0,
+		Module::<T>::u128_to_balance(10),
+		deposit_token,
+		vec![0; 32],
+	).expect("submit should work");
+	let proposal_id = ProposalCount::get(dao_id) - 1;
+	Module::<T>::sponsor_proposal(RawOrigin::Signed(sponsor.clone()).into(), dao_id, proposal_id).expect("sponsor should work");
+	proposal_id
+}
+
+/// Fill the queue with `q` sponsored proposals, the first one is voted yes by the summoner
+fn fill_queue<T: Config>(dao_id: DaoId, summoner: &T::AccountId, q: u32) {
+	for i in 0..q {
+		sponsored_proposal::<T>(dao_id, summoner, i);
+	}
+	set_period::<T>(dao_id, 1);
+	Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1).expect("vote should work");
+}
+
+/// Process the first proposal of the queue, so that the summoner can ragequit
+fn process_first<T: Config>(dao_id: DaoId, summoner: &T::AccountId, q: u32) {
+	fill_queue::<T>(dao_id, summoner, q);
+	set_period::<T>(dao_id, 5);
+	Module::<T>::process_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0).expect("process should work");
+}
+
+/// Jail a new member through a guild kick proposal, returns the jailed member
+fn jailed_member<T: Config>(dao_id: DaoId, summoner: &T::AccountId) -> T::AccountId {
+	// the first proposal adds the member, the second one kicks it
+	fill_queue::<T>(dao_id, summoner, 1);
+	set_period::<T>(dao_id, 5);
+	Module::<T>::process_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0).expect("process should work");
+	let member: T::AccountId = account("proposer", 0, SEED);
+	Module::<T>::submit_guild_kick_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, member.clone(), vec![0; 32])
+		.expect("submit should work");
+	Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1).expect("sponsor should work");
+	set_period::<T>(dao_id, 6);
+	Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1, 1).expect("vote should work");
+	set_period::<T>(dao_id, 10);
+	Module::<T>::process_guild_kick_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1).expect("process should work");
+	member
+}
+
+/// Pass a vesting proposal of the summoner, returns the id of its payout stream which starts in period 5
+fn payout_stream<T: Config>(dao_id: DaoId, summoner: &T::AccountId) -> u128 {
+	let deposit_token = Module::<T>::deposit_token(dao_id);
+	let schedule = VestingSchedule { cliff: 0, periods: 10, per_period: 10 };
+	Module::<T>::submit_vesting_proposal(
+		RawOrigin::Signed(summoner.clone()).into(),
+		dao_id,
+		summoner.clone(),
+		Module::<T>::u128_to_balance(100),
+		deposit_token,
+		1,
+		0,
+		deposit_token,
+		schedule,
+		vec![0; 32],
+	).expect("submit should work");
+	Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0).expect("sponsor should work");
+	set_period::<T>(dao_id, 1);
+	Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1).expect("vote should work");
+	set_period::<T>(dao_id, 5);
+	Module::<T>::process_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0).expect("process should work");
+	0
+}
+
+/// Sign `message` by a new vote signer, returns its account and the signature. The benchmarks expect the
+/// runtime's sr25519 `MultiSignature`, or a signature which decodes from the signer and the message like the
+/// mock's `TestSignature`
+fn vote_signer<T: Config>(index: u32, message: &[u8]) -> (T::AccountId, T::OffchainSignature) {
+	let who: T::AccountId = account("signer", index, SEED);
+	if let Ok(signature) = T::OffchainSignature::decode(&mut &(who.clone(), message).encode()[..]) {
+		if signature.verify(message, &who) {
+			return (who, signature);
+		}
+	}
+	let public = sp_io::crypto::sr25519_generate(VOTE_KEY_TYPE, None);
+	let signature = sp_io::crypto::sr25519_sign(VOTE_KEY_TYPE, &public, message).expect("the key was just generated");
+	let who = T::AccountId::decode(&mut &public.encode()[..]).expect("accounts should be sr25519 public keys");
+	// `MultiSignature::Sr25519`
+	let signature = T::OffchainSignature::decode(&mut &(1u8, signature).encode()[..]).expect("signatures should be a MultiSignature");
+	(who, signature)
+}
+
+/// A change of every setting of the benchmarked DAO, the period duration is doubled
+fn parameter_change<T: Config>() -> ParameterChangeOf<T> {
+	ParameterChange {
+		period_duration: Some(PERIOD_DURATION * 2),
+		voting_period_length: Some(2),
+		grace_period_length: Some(2),
+		dilution_bound: Some(1),
+		proposal_deposit: Some(Module::<T>::u128_to_balance(200)),
+		processing_reward: Some(Module::<T>::u128_to_balance(100)),
+	}
+}
+
+benchmarks! {
+	where_clause { where T: pallet_balances::Trait, <T as Config>::Call: From<pallet_balances::Call<T>> }
+
+	_ { }
+
+	summon {
+		let s in 1 .. MAX_SUMMONERS;
+		let t in 1 .. T::MaxTokens::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let approved_tokens = tokens::<T>(t);
+		fund::<T>(&caller, &approved_tokens);
+		let summoners: Vec<(T::AccountId, u128, u128)> = (0..s).map(|i| (account("summoner", i, SEED), 1, 0)).collect();
+	}: _(
+		RawOrigin::Signed(caller),
+		summoners,
+		approved_tokens,
+		Module::<T>::u128_to_balance(1_000_000),
+		PERIOD_DURATION,
+		2,
+		2,
+		1,
+		Module::<T>::u128_to_balance(100),
+		Module::<T>::u128_to_balance(50)
+	)
+	verify {
+		assert_eq!(DaoCount::get(), 1);
+		assert_eq!(TotalShares::get(0), s as u128);
+	}
+
+	submit_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, &tokens::<T>(1));
+		let deposit_token = Module::<T>::deposit_token(dao_id);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		dao_id,
+		caller.clone(),
+		Module::<T>::u128_to_balance(100),
+		deposit_token,
+		1,
+		0,
+		Module::<T>::u128_to_balance(10),
+		deposit_token,
+		vec![0; 32]
+	)
+	verify {
+		assert_eq!(ProposalCount::get(dao_id), 1);
+	}
+
+	submit_whitelist_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, token::<T>(1), vec![0; 32])
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).kind, ProposalKind::Whitelist);
+	}
+
+	submit_guild_kick_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, summoner, vec![0; 32])
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).kind, ProposalKind::GuildKick);
+	}
+
+	submit_action_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let call = transfer_call::<T>(1_000);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, Box::new(call), Module::<T>::u128_to_balance(1_000), T::NativeCurrencyId::get(), vec![0; 32])
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).kind, ProposalKind::Action);
+		assert!(ProposalActions::<T>::contains_key(dao_id, 0));
+	}
+
+	submit_vesting_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, &tokens::<T>(1));
+		let deposit_token = Module::<T>::deposit_token(dao_id);
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		dao_id,
+		caller.clone(),
+		Module::<T>::u128_to_balance(100),
+		deposit_token,
+		1,
+		0,
+		deposit_token,
+		VestingSchedule { cliff: 1, periods: 10, per_period: 10 },
+		vec![0; 32]
+	)
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).payment_requested, 100);
+		assert!(ProposalVestings::contains_key(dao_id, 0));
+	}
+
+	submit_cancel_stream_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let stream_id = payout_stream::<T>(dao_id, &summoner);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, stream_id, vec![0; 32])
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 1).kind, ProposalKind::CancelStream);
+		assert_eq!(ProposalStreamCancels::get(dao_id, 1), Some(stream_id));
+	}
+
+	submit_parameter_proposal {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, parameter_change::<T>(), vec![0; 32])
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).kind, ProposalKind::ParameterChange);
+		assert!(ProposalParameterChanges::<T>::contains_key(dao_id, 0));
+	}
+
+	sponsor_proposal {
+		let q in 0 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		for i in 0..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		let proposer = funded_account::<T>("proposer", q);
+		let deposit_token = Module::<T>::deposit_token(dao_id);
+		Module::<T>::submit_proposal(
+			RawOrigin::Signed(proposer.clone()).into(),
+			dao_id,
+			proposer,
+			Module::<T>::u128_to_balance(100),
+			deposit_token,
+			1,
+			0,
+			Module::<T>::u128_to_balance(10),
+			deposit_token,
+			vec![0; 32],
+		)?;
+	}: _(RawOrigin::Signed(summoner), dao_id, q as u128)
+	verify {
+		assert_eq!(ProposalQueue::get(dao_id).len(), q as usize + 1);
+	}
+
+	// the yes vote on the last proposal is changed to no, which looks for the previous yes vote through the queue
+	submit_vote {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		for i in 0..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, q as u128);
+		let last = q as u128 - 1;
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, last, 1)?;
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, last, 2)
+	verify {
+		assert_eq!(ProposalVotes::<T>::get((dao_id, ProposalQueue::get(dao_id)[last as usize]), summoner), 2);
+	}
+
+	// the yes vote on the last proposal gets a conviction, which takes the plain vote back like `submit_vote`
+	submit_conviction_vote {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		for i in 0..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, q as u128);
+		let last = q as u128 - 1;
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, last, 1)?;
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, last, 1, Conviction::Locked6x)
+	verify {
+		let proposal_id = ProposalQueue::get(dao_id)[last as usize];
+		assert_eq!(VoteConvictions::<T>::get((dao_id, proposal_id), summoner.clone()), Conviction::Locked6x);
+		assert_eq!(ShareLocks::<T>::get(dao_id, summoner).len(), 1);
+	}
+
+	// `v` summoners change their yes votes to no, the queue grows the votes like `submit_vote`
+	submit_votes_signed {
+		let v in 1 .. MAX_SUMMONERS;
+		let dao_id = DaoCount::get();
+		let message = VoteMessage { dao_id, proposal_index: 0, vote_unit: 2, nonce: 0 }.encode();
+		let signers: Vec<(T::AccountId, T::OffchainSignature)> = (0..v).map(|i| vote_signer::<T>(i, &message)).collect();
+		let caller: T::AccountId = whitelisted_caller();
+		let approved_tokens = tokens::<T>(1);
+		fund::<T>(&caller, &approved_tokens);
+		fund::<T>(&signers[0].0, &approved_tokens);
+		Module::<T>::summon(
+			RawOrigin::Signed(caller.clone()).into(),
+			signers.iter().map(|(who, _)| (who.clone(), 1, 0)).collect(),
+			approved_tokens,
+			Module::<T>::u128_to_balance(1_000_000),
+			PERIOD_DURATION,
+			2,
+			2,
+			1,
+			Module::<T>::u128_to_balance(100),
+			Module::<T>::u128_to_balance(50),
+		)?;
+		Module::<T>::set_relay_refund(T::AdminOrigin::successful_origin(), dao_id, Module::<T>::u128_to_balance(1))?;
+		sponsored_proposal::<T>(dao_id, &signers[0].0, 0);
+		set_period::<T>(dao_id, 1);
+		for (who, _) in signers.iter() {
+			Module::<T>::submit_vote(RawOrigin::Signed(who.clone()).into(), dao_id, 0, 1)?;
+		}
+		let votes: Vec<SignedVoteOf<T>> = signers.into_iter()
+			.map(|(delegate_key, signature)| SignedVote { delegate_key, proposal_index: 0, vote_unit: 2, nonce: 0, signature })
+			.collect();
+	}: _(RawOrigin::Signed(caller), dao_id, votes)
+	verify {
+		assert_eq!(Proposals::<T>::get(dao_id, 0).no_votes, v as u128);
+	}
+
+	// the yes vote on the last proposal is taken back, which looks for the previous yes vote through the queue
+	retract_vote {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		for i in 0..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, q as u128);
+		let last = q as u128 - 1;
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, last, 1)?;
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, last)
+	verify {
+		assert!(!ProposalVotes::<T>::contains_key((dao_id, ProposalQueue::get(dao_id)[last as usize]), summoner));
+	}
+
+	process_proposal {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		fill_queue::<T>(dao_id, &summoner, q);
+		set_period::<T>(dao_id, 5);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert!(Proposals::<T>::get(dao_id, 0).did_pass());
+	}
+
+	process_guild_kick_proposal {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		// kick the summoner, who is the only member
+		Module::<T>::submit_guild_kick_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, summoner.clone(), vec![0; 32])?;
+		Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0)?;
+		for i in 1..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, 1);
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1)?;
+		set_period::<T>(dao_id, 5);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert_eq!(Members::<T>::get(dao_id, summoner).loot, 1);
+	}
+
+	process_whitelist_proposal {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		Module::<T>::submit_whitelist_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, token::<T>(1), vec![0; 32])?;
+		Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0)?;
+		for i in 1..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, 1);
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1)?;
+		set_period::<T>(dao_id, 5);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert!(TokenWhitelist::<T>::get(dao_id, token::<T>(1)));
+	}
+
+	process_action_proposal {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		// the payment is moved to the action account before the call transfers it on
+		let call = transfer_call::<T>(1_000);
+		let payment = Module::<T>::u128_to_balance(1_000);
+		Module::<T>::submit_action_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, Box::new(call), payment, T::NativeCurrencyId::get(), vec![0; 32])?;
+		Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0)?;
+		for i in 1..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, 1);
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1)?;
+		set_period::<T>(dao_id, 5);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert!(Proposals::<T>::get(dao_id, 0).did_pass());
+		assert!(!ProposalActions::<T>::contains_key(dao_id, 0));
+		assert_eq!(Module::<T>::guild_bank_balance(dao_id, T::NativeCurrencyId::get()), 999_000);
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		assert_eq!(pallet_balances::Module::<T>::free_balance(&recipient), 1_000u32.into());
+	}
+
+	process_cancel_stream_proposal {
+		let q in 2 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let stream_id = payout_stream::<T>(dao_id, &summoner);
+		Module::<T>::submit_cancel_stream_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, stream_id, vec![0; 32])?;
+		Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1)?;
+		for i in 2..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, 6);
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1, 1)?;
+		set_period::<T>(dao_id, 10);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 1)
+	verify {
+		assert!(Proposals::<T>::get(dao_id, 1).did_pass());
+		assert_eq!(PayoutStreams::<T>::get(dao_id, stream_id).map(|s| s.periods), Some(6));
+	}
+
+	process_parameter_proposal {
+		let q in 1 .. MAX_QUEUE;
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		Module::<T>::submit_parameter_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, parameter_change::<T>(), vec![0; 32])?;
+		Module::<T>::sponsor_proposal(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0)?;
+		for i in 1..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		set_period::<T>(dao_id, 1);
+		Module::<T>::submit_vote(RawOrigin::Signed(summoner.clone()).into(), dao_id, 0, 1)?;
+		set_period::<T>(dao_id, 5);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert!(Proposals::<T>::get(dao_id, 0).did_pass());
+		assert_eq!(PeriodDuration::get(dao_id), PERIOD_DURATION * 2);
+		assert_eq!(Module::<T>::get_current_period(dao_id), 5);
+	}
+
+	claim_payout {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let stream_id = payout_stream::<T>(dao_id, &summoner);
+		set_period::<T>(dao_id, 9);
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, stream_id)
+	verify {
+		assert_eq!(PayoutStreams::<T>::get(dao_id, stream_id).map(|s| s.claimed), Some(50));
+	}
+
+	abort {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller, &tokens::<T>(1));
+		let deposit_token = Module::<T>::deposit_token(dao_id);
+		Module::<T>::submit_proposal(
+			RawOrigin::Signed(caller.clone()).into(),
+			dao_id,
+			caller.clone(),
+			Module::<T>::u128_to_balance(100),
+			deposit_token,
+			1,
+			0,
+			Module::<T>::u128_to_balance(10),
+			deposit_token,
+			vec![0; 32],
+		)?;
+	}: _(RawOrigin::Signed(caller), dao_id, 0)
+	verify {
+		assert!(Proposals::<T>::get(dao_id, 0).is_cancelled());
+	}
+
+	rage_quit {
+		let q in 1 .. MAX_QUEUE;
+		let t in 1 .. T::MaxTokens::get();
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, t);
+		process_first::<T>(dao_id, &summoner, q);
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, 1, 0)
+	verify {
+		assert_eq!(Members::<T>::get(dao_id, summoner).shares, 0);
+	}
+
+	rage_kick {
+		let q in 1 .. MAX_QUEUE;
+		let t in 1 .. T::MaxTokens::get();
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, t);
+		let member = jailed_member::<T>(dao_id, &summoner);
+		for i in 2..q {
+			sponsored_proposal::<T>(dao_id, &summoner, i);
+		}
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dao_id, member.clone())
+	verify {
+		assert_eq!(Members::<T>::get(dao_id, member).loot, 0);
+	}
+
+	withdraw_balance {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		process_first::<T>(dao_id, &summoner, 1);
+		Module::<T>::rage_quit(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1, 0)?;
+		let token = Module::<T>::deposit_token(dao_id);
+		let amount = UserTokenBalances::<T>::get((dao_id, summoner.clone()), token);
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, token, Module::<T>::u128_to_balance(amount))
+	verify {
+		assert_eq!(UserTokenBalances::<T>::get((dao_id, summoner), token), 0);
+	}
+
+	withdraw_balances {
+		let t in 1 .. T::MaxTokens::get();
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, t);
+		process_first::<T>(dao_id, &summoner, 1);
+		Module::<T>::rage_quit(RawOrigin::Signed(summoner.clone()).into(), dao_id, 1, 0)?;
+		let approved_tokens = tokens::<T>(t);
+		let amounts = vec![Module::<T>::u128_to_balance(0); t as usize];
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, approved_tokens, amounts, true)
+	verify {
+		assert_eq!(UserTokenBalances::<T>::get((dao_id, summoner), Module::<T>::deposit_token(dao_id)), 0);
+	}
+
+	update_delegate {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+	}: _(RawOrigin::Signed(summoner.clone()), dao_id, delegate.clone())
+	verify {
+		assert_eq!(AddressOfDelegates::<T>::get(dao_id, delegate), summoner);
+	}
+
+	set_voting_threshold {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let quorum = Perbill::from_percent(20);
+		let approval = Perbill::from_percent(66);
+	}: _(T::AdminOrigin::successful_origin(), dao_id, ProposalKind::GuildKick, quorum, approval)
+	verify {
+		assert_eq!(VotingThresholds::get(dao_id, ProposalKind::GuildKick), VotingThreshold { quorum, approval });
+	}
+
+	set_voting_mode {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+	}: _(T::AdminOrigin::successful_origin(), dao_id, VotingMode::Quadratic)
+	verify {
+		assert_eq!(VotingModes::get(dao_id), VotingMode::Quadratic);
+	}
+
+	set_relay_refund {
+		let summoner = funded_account::<T>("summoner", 0);
+		let dao_id = summon_dao::<T>(&summoner, 1);
+		let refund = Module::<T>::u128_to_balance(10);
+	}: _(T::AdminOrigin::successful_origin(), dao_id, refund)
+	verify {
+		assert_eq!(RelayRefund::<T>::get(dao_id), refund);
+	}
+
+	pause {
+		let until = frame_system::Module::<T>::block_number() + 10u32.into();
+	}: _(T::AdminOrigin::successful_origin(), CallCategory::All, Some(until))
+	verify {
+		assert!(Module::<T>::is_paused(CallCategory::Deposits));
+	}
+
+	unpause {
+		Module::<T>::pause(T::AdminOrigin::successful_origin(), CallCategory::All, None)?;
+	}: _(T::AdminOrigin::successful_origin(), CallCategory::All)
+	verify {
+		assert!(!Module::<T>::is_paused(CallCategory::Deposits));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn summon_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_summon::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_whitelist_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_whitelist_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_guild_kick_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_guild_kick_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_action_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_action_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_vesting_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_vesting_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_cancel_stream_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_cancel_stream_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_parameter_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_parameter_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn sponsor_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_sponsor_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_vote_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_vote::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_conviction_vote_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_conviction_vote::<Test>());
+		});
+	}
+
+	#[test]
+	fn submit_votes_signed_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_submit_votes_signed::<Test>());
+		});
+	}
+
+	#[test]
+	fn retract_vote_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_retract_vote::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_guild_kick_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_guild_kick_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_whitelist_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_whitelist_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_action_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_action_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_cancel_stream_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_cancel_stream_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn process_parameter_proposal_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_process_parameter_proposal::<Test>());
+		});
+	}
+
+	#[test]
+	fn claim_payout_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_claim_payout::<Test>());
+		});
+	}
+
+	#[test]
+	fn abort_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_abort::<Test>());
+		});
+	}
+
+	#[test]
+	fn rage_quit_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rage_quit::<Test>());
+		});
+	}
+
+	#[test]
+	fn rage_kick_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_rage_kick::<Test>());
+		});
+	}
+
+	#[test]
+	fn withdraw_balance_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_withdraw_balance::<Test>());
+		});
+	}
+
+	#[test]
+	fn withdraw_balances_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_withdraw_balances::<Test>());
+		});
+	}
+
+	#[test]
+	fn update_delegate_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_update_delegate::<Test>());
+		});
+	}
+
+	#[test]
+	fn set_voting_threshold_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_voting_threshold::<Test>());
+		});
+	}
+
+	#[test]
+	fn set_voting_mode_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_voting_mode::<Test>());
+		});
+	}
+
+	#[test]
+	fn set_relay_refund_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_relay_refund::<Test>());
+		});
+	}
+
+	#[test]
+	fn pause_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_pause::<Test>());
+		});
+	}
+
+	#[test]
+	fn unpause_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_unpause::<Test>());
+		});
+	}
+}
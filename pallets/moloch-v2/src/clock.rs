@@ -0,0 +1,45 @@
+//! Time sources which the periods of a DAO are counted in
+//!
+//! `PeriodDuration` is in the unit of the runtime's `Clock`, i.e. seconds for `TimestampClock`
+//! and blocks for `BlockNumberClock`.
+
+use sp_std::{marker::PhantomData, convert::TryInto};
+
+/// A monotonic clock, `SummonTime` and `PeriodDuration` are in its unit
+pub trait Clock {
+	/// Current time of the clock
+	fn now() -> u128;
+
+	/// Move the clock to `now`, so that benchmarks can get proposals to the period they need
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_now(now: u128);
+}
+
+/// Seconds of `pallet_timestamp`, periods follow the wall clock of the block authors
+pub struct TimestampClock<T>(PhantomData<T>);
+impl<T: pallet_timestamp::Trait> Clock for TimestampClock<T> {
+	fn now() -> u128 {
+		// the timestamp is in milli seconds
+		TryInto::<u128>::try_into(pallet_timestamp::Module::<T>::now()).unwrap_or_default() / 1000
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_now(now: u128) {
+		let moment = TryInto::<T::Moment>::try_into(now.saturating_mul(1000)).ok().unwrap_or_default();
+		pallet_timestamp::Module::<T>::set_timestamp(moment);
+	}
+}
+
+/// Block numbers, periods are deterministic and do not drift with the block time
+pub struct BlockNumberClock<T>(PhantomData<T>);
+impl<T: frame_system::Trait> Clock for BlockNumberClock<T> {
+	fn now() -> u128 {
+		TryInto::<u128>::try_into(frame_system::Module::<T>::block_number()).unwrap_or_default()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_now(now: u128) {
+		let block_number = TryInto::<T::BlockNumber>::try_into(now).ok().unwrap_or_default();
+		frame_system::Module::<T>::set_block_number(block_number);
+	}
+}
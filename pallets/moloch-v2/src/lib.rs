@@ -5,14 +5,27 @@
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 /// debug guide https://substrate.dev/recipes/runtime-printing.html
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, dispatch, debug, ensure,
-	traits::{Currency, EnsureOrigin, ReservableCurrency, OnUnbalanced, Get, ExistenceRequirement::{KeepAlive, AllowDeath}},
+	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, Parameter, transactional,
+	traits::{EnsureOrigin, Get, Filter}, weights::{Weight, GetDispatchInfo},
+	storage::{with_transaction, TransactionOutcome},
 };
-use sp_runtime::{ModuleId, traits::{ AccountIdConversion }};
+use sp_runtime::{ModuleId, DispatchError, RuntimeDebug, Perbill, traits::{ AccountIdConversion, Zero, Bounded, IntegerSquareRoot, Verify, IdentifyAccount, Dispatchable, Member as MemberT, MaybeSerializeDeserialize }};
+use orml_traits::MultiCurrency;
 use frame_support::codec::{Encode, Decode};
 use frame_system::{ensure_signed};
-use sp_std::{vec::Vec, convert::{TryInto}};
+use sp_std::{vec::Vec, boxed::Box, convert::{TryInto}};
 use pallet_timestamp;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+pub use weights::WeightInfo;
+pub use clock::Clock;
+
+pub mod clock;
+mod migrations;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 #[cfg(test)]
 mod mock;
@@ -29,6 +42,18 @@ pub enum Vote {
 	No
 }
 
+impl Vote {
+	/// The vote of a `vote_unit`, 1 for yes, 2 for no and 3 to abstain
+	pub fn from_unit(vote_unit: u8) -> Option<Vote> {
+		match vote_unit {
+			1 => Some(Vote::Yes),
+			2 => Some(Vote::No),
+			3 => Some(Vote::Null),
+			_ => None,
+		}
+	}
+}
+
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
 pub struct Member<AccountId> {
 	// the # of shares assigned to this member
@@ -45,8 +70,215 @@ pub struct Member<AccountId> {
 	pub jailed_at: u128,
 }
 
+/// What a proposal is about, each kind is processed by its own extrinsic
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ProposalKind {
+	// shares, loot, tribute and payment
+	Standard,
+	// add the tribute token to the whitelist
+	Whitelist,
+	// put the applicant in jail
+	GuildKick,
+	// dispatch a runtime call from the action account of the DAO
+	Action,
+	// stop the payout stream of a passed proposal from vesting any further
+	CancelStream,
+	// change the settings the DAO was summoned with
+	ParameterChange,
+}
+
+impl Default for ProposalKind {
+	fn default() -> Self {
+		ProposalKind::Standard
+	}
+}
+
+/// How the shares of a member are turned into the weight of its vote
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum VotingMode {
+	// one vote per share
+	Linear,
+	// the integer square root of the shares, so that a few large members can not outvote the rest
+	Quadratic,
+}
+
+impl Default for VotingMode {
+	fn default() -> Self {
+		VotingMode::Linear
+	}
+}
+
+impl VotingMode {
+	/// The weight of a vote cast with `shares`
+	pub fn vote_weight(&self, shares: u128) -> u128 {
+		match self {
+			VotingMode::Linear => shares,
+			VotingMode::Quadratic => shares.integer_sqrt(),
+		}
+	}
+}
+
+/// How long a member locks its shares when voting in exchange for a multiplied weight, like the
+/// conviction of `pallet_democracy`
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum Conviction {
+	// the weight of the shares, no lock
+	None,
+	// twice the weight, locked for a voting period after the grace period
+	Locked2x,
+	// three times the weight, locked for 2 voting periods after the grace period
+	Locked3x,
+	// four times the weight, locked for 4 voting periods after the grace period
+	Locked4x,
+	// five times the weight, locked for 8 voting periods after the grace period
+	Locked5x,
+	// six times the weight, locked for 16 voting periods after the grace period
+	Locked6x,
+}
+
+impl Default for Conviction {
+	fn default() -> Self {
+		Conviction::None
+	}
+}
+
+impl Conviction {
+	/// What the weight of a vote is multiplied by
+	pub fn multiplier(&self) -> u128 {
+		match self {
+			Conviction::None => 1,
+			Conviction::Locked2x => 2,
+			Conviction::Locked3x => 3,
+			Conviction::Locked4x => 4,
+			Conviction::Locked5x => 5,
+			Conviction::Locked6x => 6,
+		}
+	}
+
+	/// Voting periods the shares stay locked after the grace period of the proposal
+	pub fn lock_periods(&self) -> u128 {
+		match self {
+			Conviction::None => 0,
+			Conviction::Locked2x => 1,
+			Conviction::Locked3x => 2,
+			Conviction::Locked4x => 4,
+			Conviction::Locked5x => 8,
+			Conviction::Locked6x => 16,
+		}
+	}
+}
+
+/// Shares of a member which can not be ragequit before a period
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ShareLock {
+	// the proposal whose vote placed the lock
+	pub proposal_id: u128,
+	pub shares: u128,
+	// the first period in which the shares can be ragequit again
+	pub until: u128,
+}
+
+/// Lifecycle of a proposal, only Submitted, Sponsored, Processed and Cancelled are stored,
+/// Voting, Grace and ReadyToProcess are derived from the current period by `proposal_status`
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ProposalStatus {
+	Submitted,
+	Sponsored,
+	Voting,
+	Grace,
+	ReadyToProcess,
+	Processed { passed: bool },
+	Cancelled,
+}
+
+impl Default for ProposalStatus {
+	fn default() -> Self {
+		ProposalStatus::Submitted
+	}
+}
+
+/// Why a proposal fails when it is processed, checked in this order
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum FailureReason {
+	// more shares and loot than the dilution bound allows when the last yes vote was cast
+	DilutionBoundExceeded,
+	// the applicant is in jail
+	ApplicantJailed,
+	// fewer shares voted than the quorum of the proposal kind
+	QuorumNotReached,
+	// no more yes votes than no votes
+	NotEnoughYesVotes,
+	// fewer yes votes than the approval threshold of the proposal kind
+	ApprovalNotReached,
+	// the guild bank can not afford the payment of a standard or action proposal besides the payout streams
+	PaymentExceedsGuildBank,
+	// the whitelist is full for a whitelist proposal
+	WhitelistFull,
+	// the settings of a parameter change proposal are no longer valid, e.g. after another change of the deposit
+	InvalidParameters,
+}
+
+/// Share of `TotalShares` a proposal of some kind needs to pass, on top of more yes votes than no votes
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct VotingThreshold {
+	// minimum shares voting yes or no
+	pub quorum: Perbill,
+	// minimum shares voting yes
+	pub approval: Perbill,
+}
+
+/// What processing a proposal would do, returned by `preview_process`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ProcessPreview<CurrencyId> {
+	pub passed: bool,
+	// None if passed
+	pub failure: Option<FailureReason>,
+	// credited to the processor
+	pub reward_token: CurrencyId,
+	pub reward: u128,
+}
+
+/// Storage layout version, used by `on_runtime_upgrade` to decide which migrations to run
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	// the single-DAO layout, its storage isn't keyed by `DaoId` and proposals carry `flags: [bool; 6]`
+	V1_0_0,
+	// proposals carry `kind` and `status`
+	V2_0_0,
+	// the first unprocessed proposal of every queue is tracked in `NextToProcess`
+	V3_0_0,
+	// `SummonTime` is in the unit of the `Clock` instead of a `pallet_timestamp` moment
+	V4_0_0,
+	// shares of members and DAOs are checkpointed for voting
+	V5_0_0,
+	// proposals carry the `voting_mode` and the shares voting yes and no besides the weighted votes
+	V6_0_0,
+	// the unclaimed payments of payout streams are committed in `StreamCommitments`
+	V7_0_0,
+	// proposals carry the voting and grace period lengths they were sponsored with
+	V8_0_0,
+	// escrows carry the processing reward their proposals were sponsored with
+	V9_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
 #[derive(Encode, Decode, Default, Clone, PartialEq)]
-pub struct Proposal<AccountId> {
+pub struct Proposal<AccountId, CurrencyId> {
     // the account that submitted the proposal (can be non-member)
 	pub proposer: AccountId,
 	// the applicant who wishes to become a member - this key will be used for withdrawals (doubles as guild kick target for gkick proposals)
@@ -59,26 +291,190 @@ pub struct Proposal<AccountId> {
 	pub loot_requested: u128,
 	// amount of tokens requested as payment
 	pub payment_requested: u128,
+	// payment token
+	pub payment_token: CurrencyId,
 	// amount of tokens offered as tribute
 	pub tribute_offered: u128,
-	// [sponsored, processed, didPass, cancelled, whitelist, guildkick]
-	pub flags: [bool; 6],
+	// tribute token, doubles as the token to whitelist for whitelist proposals
+	pub tribute_token: CurrencyId,
+	// standard, whitelist or guild kick
+	pub kind: ProposalKind,
+	// submitted, sponsored, processed or cancelled
+	pub status: ProposalStatus,
 	// the period in which voting can start for this proposal
 	pub starting_period: u128,
-	// the total number of YES votes for this proposal
+	// the voting period length of the DAO when the proposal was sponsored
+	pub voting_period_length: u128,
+	// the grace period length of the DAO when the proposal was sponsored
+	pub grace_period_length: u128,
+	// the voting mode of the DAO when the proposal was sponsored
+	pub voting_mode: VotingMode,
+	// the total weight of YES votes for this proposal, decides whether it passes
 	pub yes_votes: u128,
-	// the total number of NO votes for this proposal
+	// the total weight of NO votes for this proposal
 	pub no_votes: u128,
+	// the total number of shares voting YES, counted toward the quorum and the approval
+	pub yes_shares: u128,
+	// the total number of shares voting NO, counted toward the quorum
+	pub no_shares: u128,
 	// proposal details - Must be ascii chars, limited length
 	pub details: Vec<u8>,
 	// the maximum # of total shares encountered at a yes vote on this proposal
 	pub max_total_shares_at_yes: u128,
 }
 
+impl<AccountId, CurrencyId> Proposal<AccountId, CurrencyId> {
+	/// Processed proposals have been sponsored as well
+	pub fn is_sponsored(&self) -> bool {
+		matches!(self.status, ProposalStatus::Sponsored | ProposalStatus::Processed { .. })
+	}
+
+	pub fn is_processed(&self) -> bool {
+		matches!(self.status, ProposalStatus::Processed { .. })
+	}
+
+	pub fn did_pass(&self) -> bool {
+		self.status == ProposalStatus::Processed { passed: true }
+	}
+
+	/// The first period after the voting period
+	pub fn voting_ends(&self) -> u128 {
+		self.starting_period.saturating_add(self.voting_period_length)
+	}
+
+	/// The first period after the grace period, from which on the proposal can be processed
+	pub fn grace_ends(&self) -> u128 {
+		self.voting_ends().saturating_add(self.grace_period_length)
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.status == ProposalStatus::Cancelled
+	}
+}
+
+/// Calls which the admin origin can pause when a bug is found, `rage_quit` and withdrawing are never paused so that members
+/// can always exit with their funds
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CallCategory {
+	// every call below
+	All,
+	// summoning, submitting and sponsoring proposals, which take tributes and deposits
+	Deposits,
+	// voting, retracting votes and updating delegate keys
+	Votes,
+	// processing and aborting proposals and claiming payouts, which move funds out of the guild bank
+	Transfers,
+}
+
+/// Settings of a DAO a parameter change proposal sets, the ones left `None` are kept
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ParameterChange<Balance> {
+	pub period_duration: Option<u32>,
+	pub voting_period_length: Option<u128>,
+	pub grace_period_length: Option<u128>,
+	pub dilution_bound: Option<u128>,
+	pub proposal_deposit: Option<Balance>,
+	pub processing_reward: Option<Balance>,
+}
+
+/// How the payment of a standard proposal vests once it passes, instead of being paid at once
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule {
+	// periods after processing before the first amount vests
+	pub cliff: u128,
+	// number of periods an amount vests in
+	pub periods: u128,
+	// amount vesting in each period
+	pub per_period: u128,
+}
+
+/// The payment of a passed proposal vesting in the guild bank, claimed by the recipient over time
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PayoutStream<AccountId, CurrencyId> {
+	pub recipient: AccountId,
+	pub token: CurrencyId,
+	// the first period in which an amount vests
+	pub start: u128,
+	// cut to the periods vested so far when the stream is cancelled
+	pub periods: u128,
+	pub per_period: u128,
+	// the amount claimed by the recipient so far
+	pub claimed: u128,
+}
+
+impl<AccountId, CurrencyId> PayoutStream<AccountId, CurrencyId> {
+	/// Periods vested up to and including `period`
+	pub fn vested_periods(&self, period: u128) -> u128 {
+		if period < self.start {
+			0
+		} else {
+			(period - self.start).saturating_add(1).min(self.periods)
+		}
+	}
+
+	/// Amount vested up to and including `period`
+	pub fn vested(&self, period: u128) -> u128 {
+		self.vested_periods(period).saturating_mul(self.per_period)
+	}
+
+	/// Amount vesting over all the periods
+	pub fn total(&self) -> u128 {
+		self.periods.saturating_mul(self.per_period)
+	}
+
+	/// Amount the recipient can still claim, now or once it vests
+	pub fn unclaimed(&self) -> u128 {
+		self.total().saturating_sub(self.claimed)
+	}
+}
+
+/// Tokens held in the escrow for a proposal until it's processed or aborted
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct Escrow<AccountId, CurrencyId> {
+	// the account which paid the tribute, refunded if the proposal fails or gets aborted
+	pub tribute_payer: AccountId,
+	pub tribute_token: CurrencyId,
+	pub tribute: u128,
+	// the account which paid the proposal deposit when sponsoring, gets the deposit back less the processing reward
+	pub deposit_payer: AccountId,
+	pub deposit_token: CurrencyId,
+	pub deposit: u128,
+	// the processing reward of the DAO when the proposal was sponsored, at most the deposit
+	pub processing_reward: u128,
+}
+
+/// What the delegate key of a member signs off-chain for `submit_votes_signed`, SCALE encoded
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VoteMessage {
+	pub dao_id: DaoId,
+	pub proposal_index: u128,
+	pub vote_unit: u8,
+	// the next nonce of the delegate key in the DAO, see `VoteNonces`
+	pub nonce: u64,
+}
+
+/// A vote signed off-chain, relayed by `submit_votes_signed`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SignedVote<AccountId, Signature> {
+	pub delegate_key: AccountId,
+	pub proposal_index: u128,
+	pub vote_unit: u8,
+	pub nonce: u64,
+	// of the `VoteMessage` with the DAO id and the fields above
+	pub signature: Signature,
+}
+
+/// Identifier of a summoned DAO, each DAO keeps its own parameters, members and proposal queue
+pub type DaoId = u32;
+
 type MemberOf<T> = Member<<T as frame_system::Trait>::AccountId>;
-type ProposalOf<T> = Proposal<<T as frame_system::Trait>::AccountId>;
-type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
-type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+type ProposalOf<T> = Proposal<<T as frame_system::Trait>::AccountId, <T as Config>::CurrencyId>;
+type EscrowOf<T> = Escrow<<T as frame_system::Trait>::AccountId, <T as Config>::CurrencyId>;
+type ParameterChangeOf<T> = ParameterChange<BalanceOf<T>>;
+type PayoutStreamOf<T> = PayoutStream<<T as frame_system::Trait>::AccountId, <T as Config>::CurrencyId>;
+type SignedVoteOf<T> = SignedVote<<T as frame_system::Trait>::AccountId, <T as Config>::OffchainSignature>;
+type BalanceOf<T> = <<T as Config>::Currency as MultiCurrency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 /// Configure the pallet by specifying the parameters and types on which it depends.
 pub trait Config: pallet_timestamp::Trait + frame_system::Trait {
@@ -92,11 +488,17 @@ pub trait Config: pallet_timestamp::Trait + frame_system::Trait {
     // The runtime must supply this pallet with an Event type that satisfies the pallet's requirements.
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
-	/// The currency trait.
-	type Currency: ReservableCurrency<Self::AccountId>;
+	/// The identifier of tokens which can be whitelisted by a DAO.
+	type CurrencyId: Parameter + MemberT + Copy + Default + Ord + MaybeSerializeDeserialize;
+
+	/// The multi currency trait, tributes, payments and deposits are denominated in whitelisted tokens.
+	type Currency: MultiCurrency<Self::AccountId, CurrencyId = Self::CurrencyId>;
+
+	/// The native token, DAO 0 keeps the funds migrated from the single-DAO layout in it.
+	type NativeCurrencyId: Get<Self::CurrencyId>;
 
-	/// What to do with slashed funds.
-	type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+	/// The time source periods are counted in, e.g. `clock::TimestampClock` or `clock::BlockNumberClock`.
+	type Clock: Clock;
 
 	// maximum length of voting period
 	type MaxVotingPeriodLength: Get<u128>;
@@ -110,7 +512,32 @@ pub trait Config: pallet_timestamp::Trait + frame_system::Trait {
 	// maximum number of shares
 	type MaxShares: Get<u128>;
 
-	
+	// maximum number of whitelisted tokens of a DAO
+	type MaxTokens: Get<u32>;
+
+	/// Weight of `on_initialize` spent on processing ready proposals every block, zero disables the auto-processor.
+	type AutoProcessBudget: Get<Weight>;
+
+	/// Where the processing rewards of auto-processed proposals go, `None` for the guild bank of the DAO.
+	type AutoProcessReward: Get<Option<Self::AccountId>>;
+
+	/// A runtime call carried by an action proposal, dispatched from the action account of the DAO when it passes.
+	type Call: Parameter + Dispatchable<Origin = Self::Origin> + GetDispatchInfo;
+
+	/// The calls action proposals may carry, calls of this pallet should be filtered out.
+	type CallFilter: Filter<<Self as Config>::Call>;
+
+	/// Maximum weight of the call of an action proposal, processing an action proposal is charged for it.
+	type MaxActionWeight: Get<Weight>;
+
+	/// Signature of the votes relayed by `submit_votes_signed`, e.g. the runtime's `MultiSignature`.
+	type OffchainSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+
+	/// The key relayed votes are signed with, it identifies the delegate key of a member.
+	type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
 }
 
 // The pallet's runtime storage items.
@@ -121,35 +548,127 @@ decl_storage! {
 	trait Store for Module<T: Config> as MolochV2 {
 		// Learn more about declaring storage items:
 		// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
-		// Map, each round start with an id => bool 
-		TotalShares get(fn totoal_shares): u128;
-		TotalLoot get(fn totoal_loot): u128;
-		PeriodDuration get(fn period_duration): u32;
-        VotingPeriodLength get(fn voting_period_length): u128;
-        GracePeriodLength get(fn grace_period_length): u128;
-		ProposalCount get(fn proposal_count): u128;
-        ProposalDeposit get(fn proposal_deposit): BalanceOf<T>;
-        DilutionBound get(fn dilution_bound): u128;
-        ProcessingReward get(fn processing_reward): BalanceOf<T>;
-		SummonTime get(fn summon_time): T::Moment;
-		Members get(fn members): map hasher(blake2_128_concat) T::AccountId  => MemberOf<T>;
-		AddressOfDelegates get(fn address_of_delegate): map hasher(blake2_128_concat) T::AccountId  => T::AccountId;
-		ProposalQueue get(fn proposal_queue): Vec<u128>;
-		Proposals get(fn proposals): map hasher(blake2_128_concat) u128 => ProposalOf<T>;
-		ProsedToKick get(fn proposed_to_kick): map hasher(blake2_128_concat) T::AccountId => bool;
-		ProposalVotes get(fn proposal_vote): double_map hasher(blake2_128_concat) u128, hasher(blake2_128_concat) T::AccountId => u8;
+		// Layout of the stored proposals, new chains start with the latest one
+		StorageVersion get(fn storage_version) build(|_| Releases::V9_0_0): Releases;
+		// Number of summoned DAOs, also the id of the next DAO to be summoned
+		DaoCount get(fn dao_count): DaoId;
+		// Map, each DAO id => its own settings and states
+		TotalShares get(fn totoal_shares): map hasher(blake2_128_concat) DaoId => u128;
+		TotalLoot get(fn totoal_loot): map hasher(blake2_128_concat) DaoId => u128;
+		// period duration in the unit of the `Clock`
+		PeriodDuration get(fn period_duration): map hasher(blake2_128_concat) DaoId => u32;
+		VotingPeriodLength get(fn voting_period_length): map hasher(blake2_128_concat) DaoId => u128;
+		GracePeriodLength get(fn grace_period_length): map hasher(blake2_128_concat) DaoId => u128;
+		ProposalCount get(fn proposal_count): map hasher(blake2_128_concat) DaoId => u128;
+		ProposalDeposit get(fn proposal_deposit): map hasher(blake2_128_concat) DaoId => BalanceOf<T>;
+		DilutionBound get(fn dilution_bound): map hasher(blake2_128_concat) DaoId => u128;
+		ProcessingReward get(fn processing_reward): map hasher(blake2_128_concat) DaoId => BalanceOf<T>;
+		// `Clock` time at which the DAO was summoned, its period 0 starts then
+		SummonTime get(fn summon_time): map hasher(blake2_128_concat) DaoId => u128;
+		// (`Clock` time, period) the periods are counted from since the period duration was last changed,
+		// so that the periods already passed keep their numbers
+		PeriodAnchor get(fn period_anchor): map hasher(blake2_128_concat) DaoId => Option<(u128, u128)>;
+		Members get(fn members): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => MemberOf<T>;
+		AddressOfDelegates get(fn address_of_delegate): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => T::AccountId;
+		ProposalQueue get(fn proposal_queue): map hasher(blake2_128_concat) DaoId => Vec<u128>;
+		Proposals get(fn proposals): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => ProposalOf<T>;
+		ProsedToKick get(fn proposed_to_kick): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => bool;
+		// whitelisted tokens of a DAO, the first one is used for proposal deposits and processing rewards
+		ApprovedTokens get(fn approved_tokens): map hasher(blake2_128_concat) DaoId => Vec<T::CurrencyId>;
+		TokenWhitelist get(fn token_whitelist): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::CurrencyId => bool;
+		ProposedToWhitelist get(fn proposed_to_whitelist): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::CurrencyId => bool;
+		// internal ledger, (dao_id, account), token => balance, funds are held by the DAO's account_id
+		// the account_id itself stands for the guild bank and the custody_account for the escrow
+		UserTokenBalances get(fn user_token_balances): double_map hasher(blake2_128_concat) (DaoId, T::AccountId), hasher(blake2_128_concat) T::CurrencyId => u128;
+		// tribute and deposit escrowed for each pending proposal, the escrow's ledger balance is the sum of them
+		ProposalEscrows get(fn proposal_escrow): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => EscrowOf<T>;
+		// (dao_id, proposal_id), member => vote
+		ProposalVotes get(fn proposal_vote): double_map hasher(blake2_128_concat) (DaoId, u128), hasher(blake2_128_concat) T::AccountId => u8;
+		// (dao_id, proposal_id), member => shares counted for its vote, so that the vote can be taken back
+		VoteWeights get(fn vote_weight): double_map hasher(blake2_128_concat) (DaoId, u128), hasher(blake2_128_concat) T::AccountId => u128;
+		// (dao_id, proposal_id), member => conviction of its vote, none if not stored
+		VoteConvictions get(fn vote_conviction): double_map hasher(blake2_128_concat) (DaoId, u128), hasher(blake2_128_concat) T::AccountId => Conviction;
+		// shares of a member locked by its conviction votes, expired locks are pruned when a new one is placed
+		ShareLocks get(fn share_locks): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => Vec<ShareLock>;
+		// the total number of abstaining shares of a proposal, they count toward the quorum only
+		AbstainVotes get(fn abstain_votes): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => u128;
+		// (period, shares) of a member after each change of its shares, the last one of a period is kept,
+		// those before the starting period of the oldest unprocessed proposal are pruned
+		ShareCheckpoints get(fn share_checkpoints): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => Vec<(u128, u128)>;
+		// (period, total shares) of a DAO after each change of its total shares
+		TotalShareCheckpoints get(fn total_share_checkpoints): map hasher(blake2_128_concat) DaoId => Vec<(u128, u128)>;
+		// how the votes on proposals sponsored from now on are weighted, linear by default
+		VotingModes get(fn voting_mode): map hasher(blake2_128_concat) DaoId => VotingMode;
+		// quorum and approval of each proposal kind, none by default
+		VotingThresholds get(fn voting_threshold): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) ProposalKind => VotingThreshold;
+		// queue index of the first unprocessed proposal of a DAO, the next one for the auto-processor
+		NextToProcess get(fn next_to_process): map hasher(blake2_128_concat) DaoId => u128;
+		// the call of an action proposal until it is processed
+		ProposalActions get(fn proposal_action): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => Option<<T as Config>::Call>;
+		// the vesting schedule of the payment of a standard proposal until it is processed
+		ProposalVestings get(fn proposal_vesting): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => Option<VestingSchedule>;
+		// payout streams of passed proposals by proposal id, removed once fully claimed
+		PayoutStreams get(fn payout_stream): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => Option<PayoutStreamOf<T>>;
+		// the unclaimed payments of the payout streams by token, which stay in the guild bank but are not its to spend
+		StreamCommitments get(fn stream_commitment): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::CurrencyId => u128;
+		// the payout stream a cancel stream proposal stops, until it is processed
+		ProposalStreamCancels get(fn proposal_stream_cancel): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => Option<u128>;
+		// the settings a parameter change proposal sets, until it is processed
+		ProposalParameterChanges get(fn proposal_parameter_change): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) u128 => Option<ParameterChangeOf<T>>;
+		// the nonce the next relayed vote signed by a delegate key has to carry
+		VoteNonces get(fn vote_nonce): double_map hasher(blake2_128_concat) DaoId, hasher(blake2_128_concat) T::AccountId => u64;
+		// deposit tokens the guild bank refunds to the relayer of a signed vote, none by default
+		RelayRefund get(fn relay_refund): map hasher(blake2_128_concat) DaoId => BalanceOf<T>;
+		// (dao_id, proposal_id), member => whether a relayed vote of the member on the proposal has been refunded
+		RelayRefunded get(fn relay_refunded): double_map hasher(blake2_128_concat) (DaoId, u128), hasher(blake2_128_concat) T::AccountId => bool;
+		// paused call categories => the block the pause ends at, `BlockNumber::max_value()` if it does not expire
+		Pauses get(fn pauses): map hasher(blake2_128_concat) CallCategory => Option<T::BlockNumber>;
+		// the DAO the auto-processor starts with in the next block
+		AutoProcessCursor get(fn auto_process_cursor): DaoId;
 	}
 	add_extra_genesis {
-		build(|_config| {
-			// Create pallet's internal account
-			let _ = T::Currency::make_free_balance_be(
-				&<Module<T>>::account_id(),
-				T::Currency::minimum_balance(),
-			);
-			let _ = T::Currency::make_free_balance_be(
-				&<Module<T>>::custody_account(),
-				T::Currency::minimum_balance(),
-			);
+		// founding members of a DAO summoned at genesis, as (account, shares, loot), no DAO is summoned if empty
+		config(summoners): Vec<(T::AccountId, u128, u128)>;
+		// the first token is the deposit token
+		config(approved_tokens): Vec<T::CurrencyId>;
+		config(period_duration): u32;
+		config(voting_period_length): u128;
+		config(grace_period_length): u128;
+		config(dilution_bound): u128;
+		config(proposal_deposit): BalanceOf<T>;
+		config(processing_reward): BalanceOf<T>;
+		build(|config: &GenesisConfig<T>| {
+			if config.summoners.is_empty() {
+				return;
+			}
+			<Module<T>>::validate_summon(
+				&config.summoners,
+				&config.approved_tokens,
+				config.period_duration,
+				config.voting_period_length,
+				config.grace_period_length,
+				config.dilution_bound,
+				config.proposal_deposit,
+				config.processing_reward,
+			).expect("Invalid moloch genesis config");
+			let dao_id = <Module<T>>::do_summon(
+				config.summoners.clone(),
+				config.approved_tokens.clone(),
+				config.period_duration,
+				config.voting_period_length,
+				config.grace_period_length,
+				config.dilution_bound,
+				config.proposal_deposit,
+				config.processing_reward,
+			).expect("Failed to summon the genesis DAO");
+			// Create the DAO's account which holds all the funds
+			for token in config.approved_tokens.iter() {
+				let _ = T::Currency::deposit(
+					*token,
+					&<Module<T>>::account_id(dao_id),
+					T::Currency::minimum_balance(*token),
+				);
+			}
 		});
 	}
 }
@@ -158,27 +677,67 @@ decl_storage! {
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
 	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId, 
-	        Balance = <<T as Config>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance {
+	        Balance = <<T as Config>::Currency as MultiCurrency<<T as frame_system::Trait>::AccountId>>::Balance,
+	        CurrencyId = <T as Config>::CurrencyId,
+	        BlockNumber = <T as frame_system::Trait>::BlockNumber {
 		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [proposalIndex, delegateKey, memberAddress, applicant, tokenTribute, sharesRequested] 
-		SubmitProposal(u128, AccountId, AccountId, AccountId, u128, u128),
-		/// parameters. [proposalIndex, delegateKey, memberAddress, uintVote]
-		SubmitVote(u128, AccountId, AccountId, u8),
-		/// parameters. [proposalIndex, applicant, memberAddress, tokenTribute, sharesRequested, didPass]
-		ProcessProposal(u128, AccountId, AccountId, u128, u128, bool),
-		/// parameters. [memberAddress, sharesToBurn]
-		Ragequit(AccountId, u128),
-		/// parameters. [proposalIndex, applicantAddress]
-		Abort(u128, AccountId),
-		/// parameters. [memberAddress, newDelegateKey]
-		UpdateDelegateKey(AccountId, AccountId),
-		/// parameters. [summoner, shares]
-		SummonComplete(AccountId, u128),
-		/// parameters. [totalShares, dilutionBond, maxTotalSharesVoteAtYes]
-		DilutionBoundExeceeds(u128, u128, u128),
+		/// parameters. [daoId, proposalIndex, delegateKey, memberAddress, applicant, tokenTribute, sharesRequested] 
+		SubmitProposal(DaoId, u128, AccountId, AccountId, AccountId, u128, u128),
+		/// parameters. [daoId, proposalIndex, delegateKey, memberAddress, uintVote]
+		SubmitVote(DaoId, u128, AccountId, AccountId, u8),
+		/// parameters. [daoId, proposalIndex, applicant, memberAddress, tokenTribute, sharesRequested, didPass]
+		ProcessProposal(DaoId, u128, AccountId, AccountId, u128, u128, bool),
+		/// parameters. [daoId, proposalIndex, tokenToWhitelist, didPass]
+		ProcessWhitelistProposal(DaoId, u128, CurrencyId, bool),
+		/// parameters. [daoId, proposalIndex, applicant, paymentToken, paymentRequested]
+		PaymentDisbursed(DaoId, u128, AccountId, CurrencyId, u128),
+		/// parameters. [daoId, memberAddress, token, amount]
+		Withdraw(DaoId, AccountId, CurrencyId, u128),
+		/// parameters. [daoId, memberAddress, sharesToBurn]
+		Ragequit(DaoId, AccountId, u128),
+		/// parameters. [daoId, proposalIndex, applicantAddress]
+		Abort(DaoId, u128, AccountId),
+		/// parameters. [daoId, memberAddress, newDelegateKey]
+		UpdateDelegateKey(DaoId, AccountId, AccountId),
+		/// parameters. [daoId, summoner, shares]
+		SummonComplete(DaoId, AccountId, u128),
+		/// parameters. [daoId, totalShares, dilutionBond, maxTotalSharesVoteAtYes]
+		DilutionBoundExeceeds(DaoId, u128, u128, u128),
 		/// parameters. [currentReserved, requiredReserved]
 		CustodyBalanceOutage(Balance, Balance),
 		CustodySucceeded(AccountId, Balance),
+		/// parameters. [daoId, proposalIndex, rewardDestination]
+		ProposalAutoProcessed(DaoId, u128, AccountId),
+		/// parameters. [daoId, proposalIndex, reason]
+		ProposalFailed(DaoId, u128, FailureReason),
+		/// parameters. [daoId, proposalIndex, delegateKey, memberAddress]
+		VoteRetracted(DaoId, u128, AccountId, AccountId),
+		/// parameters. [daoId, proposalKind, quorum, approval]
+		VotingThresholdSet(DaoId, ProposalKind, Perbill, Perbill),
+		/// parameters. [daoId, votingMode]
+		VotingModeSet(DaoId, VotingMode),
+		/// parameters. [daoId, relayer, countedVotes, refund]
+		SignedVotesRelayed(DaoId, AccountId, u32, u128),
+		/// parameters. [daoId, delegateKey, nonce, error]
+		SignedVoteFailed(DaoId, AccountId, u64, DispatchError),
+		/// parameters. [daoId, refund]
+		RelayRefundSet(DaoId, Balance),
+		/// parameters. [daoId, proposalIndex, didPass]
+		ProcessActionProposal(DaoId, u128, bool),
+		/// parameters. [daoId, proposalIndex, result]
+		ActionExecuted(DaoId, u128, dispatch::DispatchResult),
+		/// parameters. [daoId, streamId, recipient, token, startPeriod, periods, perPeriod]
+		PayoutStreamStarted(DaoId, u128, AccountId, CurrencyId, u128, u128, u128),
+		/// parameters. [daoId, streamId, recipient, amount]
+		PayoutClaimed(DaoId, u128, AccountId, u128),
+		/// parameters. [daoId, proposalIndex, streamId, didPass]
+		ProcessCancelStreamProposal(DaoId, u128, u128, bool),
+		/// parameters. [daoId, proposalIndex, didPass]
+		ProcessParameterProposal(DaoId, u128, bool),
+		/// parameters. [category, until]
+		CallsPaused(CallCategory, Option<BlockNumber>),
+		/// parameters. [category]
+		CallsUnpaused(CallCategory),
 	}
 );
 
@@ -189,7 +748,12 @@ decl_error! {
 		NoneValue,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
+		DaoNotExist,
+		DaoAlreadySummoned,
+		NoSummoner,
+		DuplicateSummoner,
 		VotingPeriodLengthTooBig,
+		PeriodDurationIsZero,
 		DilutionBoundTooBig,
 		GracePeriodLengthTooBig,
 		NoEnoughProposalDeposit,
@@ -198,6 +762,18 @@ decl_error! {
 		NotMember,
 		NotStandardProposal,
 		NotKickProposal,
+		NotWhitelistProposal,
+		NotActionProposal,
+		NotCancelStreamProposal,
+		InvalidVestingSchedule,
+		StreamNotExist,
+		NotStreamRecipient,
+		NothingToClaim,
+		NotParameterProposal,
+		NoParameterChange,
+		CallsArePaused,
+		CallFiltered,
+		ActionTooHeavy,
 		NotProposalProposer,
 		SharesOverFlow,
 		ProposalNotExist,
@@ -210,12 +786,25 @@ decl_error! {
 		PreviousProposalNotProcessed,
 		ProposalExpired,
 		InvalidVote,
+		SharesLocked,
+		BadVoteNonce,
+		BadVoteSignature,
 		MemberHasVoted,
+		MemberNotVoted,
+		VoteNotChangeable,
 		NoOverwriteDelegate,
 		NoOverwriteMember,
 		NoCustodyFound,
 		MemberInJail,
 		MemberNotInJail,
+		NoApprovedToken,
+		DuplicateToken,
+		TooManyTokens,
+		TokenNotWhitelisted,
+		TokenAlreadyWhitelisted,
+		TokenAlreadyProposed,
+		InsufficientBalance,
+		InvalidWithdrawal,
 	}
 }
 
@@ -229,375 +818,518 @@ decl_module! {
 
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v2::<T>()
+				.saturating_add(migrations::migrate_to_v3::<T>())
+				.saturating_add(migrations::migrate_to_v4::<T>())
+				.saturating_add(migrations::migrate_to_v5::<T>())
+				.saturating_add(migrations::migrate_to_v6::<T>())
+				.saturating_add(migrations::migrate_to_v7::<T>())
+				.saturating_add(migrations::migrate_to_v8::<T>())
+				.saturating_add(migrations::migrate_to_v9::<T>())
+		}
+
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			Self::auto_process(T::AutoProcessBudget::get())
+		}
+
 		const MaxVotingPeriodLength: u128 = T::MaxVotingPeriodLength::get();
 		const MaxGracePeriodLength: u128 = T::MaxGracePeriodLength::get();
 		const MaxDilutionBound: u128 = T::MaxDilutionBound::get();
 		const MaxShares: u128 = T::MaxShares::get();
+		const MaxTokens: u32 = T::MaxTokens::get();
+		const AutoProcessBudget: Weight = T::AutoProcessBudget::get();
+		const MaxActionWeight: Weight = T::MaxActionWeight::get();
 		
-		/// Summon a group or orgnization
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn summon(origin, period_duration: u32, voting_period_length: u128,
+		/// Summon a group or orgnization, every summon creates a new DAO with a fresh id, so a live DAO can never be re-summoned.
+		/// `summoners` are the founding members with their (shares, loot), `approved_tokens` is the initial token whitelist
+		/// whose first token is used for deposits. The caller endows the DAO's internal accounts
+		/// and seeds the guild bank with `initial_tribute` of the deposit token
+		#[weight = T::WeightInfo::summon(summoners.len() as u32, approved_tokens.len() as u32)]
+		#[transactional]
+		pub fn summon(origin, summoners: Vec<(T::AccountId, u128, u128)>, approved_tokens: Vec<T::CurrencyId>,
+			          #[compact] initial_tribute: BalanceOf<T>,
+			          period_duration: u32, voting_period_length: u128,
 			          grace_period_length: u128, dilution_bound: u128,
 					  #[compact] proposal_deposit: BalanceOf<T>, 
 					  #[compact]  processing_reward: BalanceOf<T>) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(voting_period_length <= T::MaxVotingPeriodLength::get(), Error::<T>::VotingPeriodLengthTooBig);
-			ensure!(grace_period_length <= T::MaxGracePeriodLength::get(), Error::<T>::GracePeriodLengthTooBig);
-			ensure!(dilution_bound <= T::MaxDilutionBound::get(), Error::<T>::DilutionBoundTooBig);
-			ensure!(proposal_deposit >= processing_reward, Error::<T>::NoEnoughProposalDeposit);
-
-			SummonTime::<T>::put(pallet_timestamp::Module::<T>::now());
-			PeriodDuration::put(period_duration);
-			VotingPeriodLength::put(voting_period_length);
-			GracePeriodLength::put(grace_period_length);
-			DilutionBound::put(dilution_bound);
-
-			ProposalDeposit::<T>::put(proposal_deposit);
-			ProcessingReward::<T>::put(processing_reward);
-			let member = Member {
-				shares: 1,
-				highest_index_yes_vote: 0,
-				loot: 0,
-				jailed_at: 0,
-				exists: true,
-				delegate_key: who.clone(),
-			};
-			Members::<T>::insert(who.clone(), member);
-			AddressOfDelegates::<T>::insert(who.clone(), who.clone());
-			TotalShares::put(1);
-			Self::deposit_event(RawEvent::SummonComplete(who, 1));
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			Self::validate_summon(&summoners, &approved_tokens, period_duration, voting_period_length, grace_period_length,
+			                      dilution_bound, proposal_deposit, processing_reward)?;
+
+			let dao_id = DaoCount::get();
+			// the summoner endows the DAO's account, so that it's never reaped
+			for token in approved_tokens.iter() {
+				let minimum_balance = T::Currency::minimum_balance(*token);
+				if !minimum_balance.is_zero() {
+					T::Currency::transfer(*token, &who, &Self::account_id(dao_id), minimum_balance)?;
+				}
+			}
+			// seed the guild bank
+			let deposit_token = approved_tokens[0];
+			if !initial_tribute.is_zero() {
+				T::Currency::transfer(deposit_token, &who, &Self::account_id(dao_id), initial_tribute)?;
+			}
+
+			Self::do_summon(summoners, approved_tokens, period_duration, voting_period_length, grace_period_length, dilution_bound,
+			                proposal_deposit, processing_reward)?;
+			Self::add_to_balance(dao_id, &Self::account_id(dao_id), deposit_token, Self::balance_to_u128(initial_tribute))?;
 			Ok(())
 		}
 
-		/// Anyone can submit proposal, but need to ensure enough tokens
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn submit_proposal(origin, applicant: T::AccountId, #[compact] tribute_offered: BalanceOf<T>,
-			                   shares_requested: u128, loot_requested: u128, #[compact] payment_requested: BalanceOf<T>, 
+		/// Anyone can submit proposal, but need to ensure enough tokens, tribute and payment can be any whitelisted token
+		#[weight = T::WeightInfo::submit_proposal()]
+		#[transactional]
+		pub fn submit_proposal(origin, dao_id: DaoId, applicant: T::AccountId, #[compact] tribute_offered: BalanceOf<T>,
+			                   tribute_token: T::CurrencyId, shares_requested: u128, loot_requested: u128,
+			                   #[compact] payment_requested: BalanceOf<T>, payment_token: T::CurrencyId,
 							   details: Vec<u8>) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			if Members::<T>::contains_key(who.clone()) {
-				ensure!(Members::<T>::get(who.clone()).jailed_at == 0, Error::<T>::MemberInJail);
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			Self::do_submit_proposal(dao_id, who, applicant, Self::balance_to_u128(tribute_offered), tribute_token,
+			                         shares_requested, loot_requested, Self::balance_to_u128(payment_requested), payment_token, details)?;
+			Ok(())
+		}
+
+		/// Submit a standard proposal whose payment vests by `schedule` once it passes, `periods * per_period` in total.
+		/// The recipient claims the vested amount with `claim_payout`
+		#[weight = T::WeightInfo::submit_vesting_proposal()]
+		#[transactional]
+		pub fn submit_vesting_proposal(origin, dao_id: DaoId, applicant: T::AccountId, #[compact] tribute_offered: BalanceOf<T>,
+		                               tribute_token: T::CurrencyId, shares_requested: u128, loot_requested: u128,
+		                               payment_token: T::CurrencyId, schedule: VestingSchedule, details: Vec<u8>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(schedule.periods > 0 && schedule.per_period > 0, Error::<T>::InvalidVestingSchedule);
+			let payment_requested = schedule.periods.checked_mul(schedule.per_period).ok_or(Error::<T>::InvalidVestingSchedule)?;
+			let proposal_index = Self::do_submit_proposal(dao_id, who, applicant, Self::balance_to_u128(tribute_offered), tribute_token,
+			                                              shares_requested, loot_requested, payment_requested, payment_token, details)?;
+			ProposalVestings::insert(dao_id, proposal_index, schedule);
+			Ok(())
+		}
+
+		/// The recipient of a payout stream claims the amount vested so far to its internal balance
+		#[weight = T::WeightInfo::claim_payout()]
+		#[transactional]
+		pub fn claim_payout(origin, dao_id: DaoId, stream_id: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			let mut stream = PayoutStreams::<T>::get(dao_id, stream_id).ok_or(Error::<T>::StreamNotExist)?;
+			ensure!(who == stream.recipient, Error::<T>::NotStreamRecipient);
+			let vested = stream.vested(Self::get_current_period(dao_id));
+			let amount = vested.saturating_sub(stream.claimed);
+			ensure!(amount > 0, Error::<T>::NothingToClaim);
+			Self::internal_transfer(dao_id, &Self::account_id(dao_id), &who, stream.token, amount)?;
+			StreamCommitments::<T>::mutate(dao_id, stream.token, |committed| *committed = committed.saturating_sub(amount));
+			stream.claimed = vested;
+			if stream.claimed >= stream.total() {
+				PayoutStreams::<T>::remove(dao_id, stream_id);
+			} else {
+				PayoutStreams::<T>::insert(dao_id, stream_id, stream);
 			}
-			let total_requested = loot_requested.checked_add(shares_requested).unwrap();
-			let future_shares = TotalShares::get().checked_add(total_requested).unwrap();
-			ensure!(future_shares <= T::MaxShares::get(), Error::<T>::SharesOverFlow);
-
-			// collect proposal deposit from proposer and store it in the Moloch until the proposal is processed
-			let _ = T::Currency::transfer(&who, &Self::custody_account(), tribute_offered, KeepAlive);
-
-			let tribute_offered_num = Self::balance_to_u128(tribute_offered);
-			let payment_requested_num = Self::balance_to_u128(payment_requested);
-			let flags = [false; 6];
-			Self::create_proposal(who.clone(), applicant.clone(), shares_requested, loot_requested, 
-			                      tribute_offered_num, payment_requested_num, details, flags);
+			Self::deposit_event(RawEvent::PayoutClaimed(dao_id, stream_id, who, amount));
+			Ok(())
+		}
+
+		/// propose a whitelist proposal, the token can be used as tribute and payment once the proposal passed
+		#[weight = T::WeightInfo::submit_whitelist_proposal()]
+		pub fn submit_whitelist_proposal(origin, dao_id: DaoId, token_to_whitelist: T::CurrencyId, details: Vec<u8>) -> dispatch::DispatchResult  {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			ensure!(!TokenWhitelist::<T>::get(dao_id, token_to_whitelist), Error::<T>::TokenAlreadyWhitelisted);
+			ensure!(ApprovedTokens::<T>::get(dao_id).len() < T::MaxTokens::get() as usize, Error::<T>::TooManyTokens);
+
+			let payment_token = Self::deposit_token(dao_id);
+			Self::create_proposal(dao_id, who.clone(), who.clone(), 0, 0, 0, token_to_whitelist, 0, payment_token, details, ProposalKind::Whitelist);
 			Ok(())
 		}
 
 		/// propose a guild kick proposal
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn submit_guild_kick_proposal(origin, member_to_kick: T::AccountId, details: Vec<u8>) -> dispatch::DispatchResult  {
+		#[weight = T::WeightInfo::submit_guild_kick_proposal()]
+		pub fn submit_guild_kick_proposal(origin, dao_id: DaoId, member_to_kick: T::AccountId, details: Vec<u8>) -> dispatch::DispatchResult  {
 			let who = ensure_signed(origin)?;
-			ensure!(Members::<T>::contains_key(member_to_kick.clone()), Error::<T>::NotMember);
-			let member = Members::<T>::get(member_to_kick.clone());
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(Members::<T>::contains_key(dao_id, member_to_kick.clone()), Error::<T>::NotMember);
+			let member = Members::<T>::get(dao_id, member_to_kick.clone());
 			ensure!(member.shares > 0 || member.loot > 0, Error::<T>::NoEnoughShares);
 			ensure!(member.jailed_at == 0, Error::<T>::MemberInJail);
 
 			// [sponsored, processed, didPass, cancelled, whitelist, guildkick]
-			let mut flags = [false; 6];
-			flags[5] = true;
-			Self::create_proposal(who.clone(), member_to_kick.clone(), 0, 0, 0, 0, details, flags);
+			let deposit_token = Self::deposit_token(dao_id);
+			Self::create_proposal(dao_id, who.clone(), member_to_kick.clone(), 0, 0, 0, deposit_token, 0, deposit_token, details, ProposalKind::GuildKick);
+			Ok(())
+		}
+
+		/// Propose to stop the payout stream of a passed proposal, the amount vested until it is processed can still be claimed
+		#[weight = T::WeightInfo::submit_cancel_stream_proposal()]
+		pub fn submit_cancel_stream_proposal(origin, dao_id: DaoId, stream_id: u128, details: Vec<u8>) -> dispatch::DispatchResult  {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			let stream = PayoutStreams::<T>::get(dao_id, stream_id).ok_or(Error::<T>::StreamNotExist)?;
+
+			let proposal_index = ProposalCount::get(dao_id);
+			let deposit_token = Self::deposit_token(dao_id);
+			Self::create_proposal(dao_id, who, stream.recipient, 0, 0, 0, deposit_token, 0, deposit_token, details, ProposalKind::CancelStream);
+			ProposalStreamCancels::insert(dao_id, proposal_index, stream_id);
 			Ok(())
 		}
 
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn sponsor_proposal(origin, proposal_index: u128) -> dispatch::DispatchResult  {
+		/// Propose to change some settings of the DAO, they are validated as at `summon`.
+		/// Queued proposals keep their periods and lengths, the new lengths apply to proposals sponsored once it passes
+		#[weight = T::WeightInfo::submit_parameter_proposal()]
+		pub fn submit_parameter_proposal(origin, dao_id: DaoId, change: ParameterChangeOf<T>, details: Vec<u8>) -> dispatch::DispatchResult  {
 			let who = ensure_signed(origin)?;
-			ensure!(Members::<T>::contains_key(who.clone()), Error::<T>::NotMember);
-			ensure!(Proposals::<T>::contains_key(proposal_index), Error::<T>::ProposalNotExist);
-			let proposal = Proposals::<T>::get(proposal_index);
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			ensure!(change != ParameterChange::default(), Error::<T>::NoParameterChange);
+			Self::validate_parameter_change(dao_id, &change)?;
+
+			let proposal_index = ProposalCount::get(dao_id);
+			let deposit_token = Self::deposit_token(dao_id);
+			Self::create_proposal(dao_id, who.clone(), who.clone(), 0, 0, 0, deposit_token, 0, deposit_token, details, ProposalKind::ParameterChange);
+			ProposalParameterChanges::<T>::insert(dao_id, proposal_index, change);
+			Ok(())
+		}
+
+		/// Propose a runtime call for the DAO to dispatch from its action account.
+		/// If it passes, `payment_requested` is moved from the guild bank to the action account right before the call
+		#[weight = T::WeightInfo::submit_action_proposal()]
+		pub fn submit_action_proposal(origin, dao_id: DaoId, call: Box<<T as Config>::Call>,
+		                              #[compact] payment_requested: BalanceOf<T>, payment_token: T::CurrencyId,
+		                              details: Vec<u8>) -> dispatch::DispatchResult  {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			ensure!(T::CallFilter::filter(&call), Error::<T>::CallFiltered);
+			ensure!(call.get_dispatch_info().weight <= T::MaxActionWeight::get(), Error::<T>::ActionTooHeavy);
+			ensure!(TokenWhitelist::<T>::get(dao_id, payment_token), Error::<T>::TokenNotWhitelisted);
+
+			let proposal_index = ProposalCount::get(dao_id);
+			let deposit_token = Self::deposit_token(dao_id);
+			let payment_requested = Self::balance_to_u128(payment_requested);
+			Self::create_proposal(dao_id, who.clone(), who.clone(), 0, 0, 0, deposit_token, payment_requested, payment_token, details, ProposalKind::Action);
+			ProposalActions::<T>::insert(dao_id, proposal_index, *call);
+			Ok(())
+		}
+
+		#[weight = T::WeightInfo::sponsor_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn sponsor_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult  {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
+			ensure!(Members::<T>::contains_key(dao_id, who.clone()), Error::<T>::NotMember);
+			ensure!(Proposals::<T>::contains_key(dao_id, proposal_index), Error::<T>::ProposalNotExist);
+			let proposal = Proposals::<T>::get(dao_id, proposal_index);
 			// check proposal status
-			ensure!(!proposal.flags[0], Error::<T>::ProposalHasSponsored);
-			ensure!(!proposal.flags[3], Error::<T>::ProposalHasAborted);
+			ensure!(!proposal.is_sponsored(), Error::<T>::ProposalHasSponsored);
+			ensure!(!proposal.is_cancelled(), Error::<T>::ProposalHasAborted);
 			// reject in jailed memeber to process
-			if Members::<T>::contains_key(who.clone()) {
-				ensure!(Members::<T>::get(who.clone()).jailed_at == 0, Error::<T>::MemberInJail);
+			if Members::<T>::contains_key(dao_id, who.clone()) {
+				ensure!(Members::<T>::get(dao_id, who.clone()).jailed_at == 0, Error::<T>::MemberInJail);
 			}
 
-			// collect proposal deposit from proposer and store it in the Moloch until the proposal is processed
-			let _ = T::Currency::transfer(&who, &Self::account_id(), ProposalDeposit::<T>::get(), KeepAlive);
+			// whitelist proposal
+			if proposal.kind == ProposalKind::Whitelist {
+				ensure!(!TokenWhitelist::<T>::get(dao_id, proposal.tribute_token), Error::<T>::TokenAlreadyWhitelisted);
+				ensure!(!ProposedToWhitelist::<T>::get(dao_id, proposal.tribute_token), Error::<T>::TokenAlreadyProposed);
+				ensure!(ApprovedTokens::<T>::get(dao_id).len() < T::MaxTokens::get() as usize, Error::<T>::TooManyTokens);
+			}
 
-			if proposal.flags[5] {
-				ensure!(!ProsedToKick::<T>::contains_key(proposal.applicant.clone()), Error::<T>::MemberInJail);
-				ProsedToKick::<T>::insert(proposal.applicant, true);
+			// collect proposal deposit from sponsor and store it in the escrow until the proposal is processed
+			let deposit_token = Self::deposit_token(dao_id);
+			let proposal_deposit = ProposalDeposit::<T>::get(dao_id);
+			T::Currency::transfer(deposit_token, &who, &Self::account_id(dao_id), proposal_deposit)?;
+			Self::add_to_balance(dao_id, &Self::custody_account(dao_id), deposit_token, Self::balance_to_u128(proposal_deposit))?;
+			ProposalEscrows::<T>::mutate(dao_id, proposal_index, |escrow| {
+				escrow.deposit_payer = who.clone();
+				escrow.deposit_token = deposit_token;
+				escrow.deposit = Self::balance_to_u128(proposal_deposit);
+				escrow.processing_reward = Self::balance_to_u128(ProcessingReward::<T>::get(dao_id)).min(escrow.deposit);
+			});
+
+			if proposal.kind == ProposalKind::Whitelist {
+				ProposedToWhitelist::<T>::insert(dao_id, proposal.tribute_token, true);
+			}
+			if proposal.kind == ProposalKind::GuildKick {
+				ensure!(!ProsedToKick::<T>::contains_key(dao_id, proposal.applicant.clone()), Error::<T>::MemberInJail);
+				ProsedToKick::<T>::insert(dao_id, proposal.applicant, true);
 			}
-			let proposal_queue = ProposalQueue::get();
+			let proposal_queue = ProposalQueue::get(dao_id);
 			let proposal_period = match proposal_queue.len() {
 				0 => 0,
-				n => Proposals::<T>::get(proposal_queue[n-1]).starting_period
+				n => Proposals::<T>::get(dao_id, proposal_queue[n-1]).starting_period
 			};
-			let starting_period = proposal_period.max(Self::get_current_period()).checked_add(1).unwrap();
-			Proposals::<T>::mutate(proposal_index, |p| {
+			let starting_period = proposal_period.max(Self::get_current_period(dao_id)).checked_add(1).unwrap();
+			Proposals::<T>::mutate(dao_id, proposal_index, |p| {
 				p.starting_period = starting_period;
-				// sponsored
-				p.flags[0] = true;
-				p.sponsor = AddressOfDelegates::<T>::get(who.clone());
+				p.voting_period_length = VotingPeriodLength::get(dao_id);
+				p.grace_period_length = GracePeriodLength::get(dao_id);
+				p.voting_mode = VotingModes::get(dao_id);
+				p.status = ProposalStatus::Sponsored;
+				p.sponsor = AddressOfDelegates::<T>::get(dao_id, who.clone());
 			});
-			ProposalQueue::append(proposal_index);
+			ProposalQueue::append(dao_id, proposal_index);
 
 			Ok(())
 		}
 
-		/// One of the members submit a vote
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn submit_vote(origin, proposal_index: u128, vote_unit: u8) -> dispatch::DispatchResult {
+		/// One of the members submit a vote, 1 for yes, 2 for no and 3 to abstain.
+		/// The vote can be changed while the voting period is open
+		#[weight = T::WeightInfo::submit_vote(Module::<T>::proposal_queue_len(*dao_id))]
+		pub fn submit_vote(origin, dao_id: DaoId, proposal_index: u128, vote_unit: u8) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(AddressOfDelegates::<T>::contains_key(who.clone()), Error::<T>::NotMember);
-			let delegate = AddressOfDelegates::<T>::get(who.clone());
-			let member = Members::<T>::get(delegate.clone());
-			ensure!(member.shares > 0, Error::<T>::NoEnoughShares);
-			
-			let proposal_len = ProposalQueue::get().len();
-			ensure!(proposal_index < proposal_len.try_into().unwrap(), Error::<T>::ProposalNotExist);
-			let _usize_proposal_index = TryInto::<usize>::try_into(proposal_index).ok().unwrap();
-			let proposal_id = ProposalQueue::get()[_usize_proposal_index];
-			let proposal = Proposals::<T>::get(proposal_id);
-			ensure!(vote_unit < 3 && vote_unit > 0, Error::<T>::InvalidVote);
-			ensure!(Self::get_current_period() >= proposal.starting_period, Error::<T>::ProposalNotStart);
-			ensure!(
-				Self::get_current_period() <  VotingPeriodLength::get() + proposal.starting_period,
-				Error::<T>::ProposalExpired
-			);
-			ensure!(!ProposalVotes::<T>::contains_key(proposal_index, delegate.clone()), Error::<T>::MemberHasVoted);
-			ensure!(!proposal.flags[3], Error::<T>::ProposalHasAborted);
-			let vote = match vote_unit {
-				1 => Vote::Yes,
-				2 => Vote::No,
-				_ => Vote::Null
-			};
-			ProposalVotes::<T>::insert(proposal_id, delegate.clone(), vote_unit);
-
-			// update proposal
-			Proposals::<T>::mutate(proposal_id, |p| {
-				if vote == Vote::Yes {
-					p.yes_votes = proposal.yes_votes.checked_add(member.shares).unwrap();
-					if proposal_index > member.highest_index_yes_vote {	
-						Members::<T>::mutate(delegate.clone(), |mem| {
-							mem.highest_index_yes_vote = proposal_index;
-						});
-					}
-
-					// update max yes
-					let all_loot_shares = TotalShares::get().checked_add(TotalLoot::get()).unwrap();
-					if all_loot_shares > proposal.max_total_shares_at_yes {
-						p.max_total_shares_at_yes = all_loot_shares;
-					}
-				} else if vote == Vote::No {
-					p.no_votes = proposal.no_votes.checked_add(member.shares).unwrap();
-				}
-			});
-			Self::deposit_event(RawEvent::SubmitVote(proposal_index, who, delegate, vote_unit));
-			Ok(())
+			Self::ensure_not_paused(CallCategory::Votes)?;
+			Self::do_submit_vote(dao_id, who, proposal_index, vote_unit, Conviction::None)
 		}
 
-		/// Process a proposal in queue
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn process_proposal(origin, proposal_index: u128) -> dispatch::DispatchResult {
+		/// Submit a yes or no vote whose weight is multiplied by the conviction, the shares of the member can not be
+		/// ragequit until the lock of the conviction is over
+		#[weight = T::WeightInfo::submit_conviction_vote(Module::<T>::proposal_queue_len(*dao_id))]
+		pub fn submit_conviction_vote(origin, dao_id: DaoId, proposal_index: u128, vote_unit: u8, conviction: Conviction) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			let proposal_len = ProposalQueue::get().len();
-			ensure!(proposal_index < proposal_len.try_into().unwrap(), Error::<T>::ProposalNotExist);
-			let _usize_proposal_index = TryInto::<usize>::try_into(proposal_index).ok().unwrap();
-			let proposal_id = ProposalQueue::get()[_usize_proposal_index];
-			let proposal = &mut Proposals::<T>::get(proposal_id);
-			ensure!(!proposal.flags[4] && !proposal.flags[5], Error::<T>::NotStandardProposal);
-			ensure!(
-				Self::get_current_period() - VotingPeriodLength::get() - GracePeriodLength::get() >= proposal.starting_period,
-				Error::<T>::ProposalNotReady
-			);
-			ensure!(proposal.flags[1] == false, Error::<T>::ProposalHasProcessed);
-			ensure!(proposal_index == 0 || Proposals::<T>::get(ProposalQueue::get()[_usize_proposal_index - 1]).flags[1], 
-			        Error::<T>::PreviousProposalNotProcessed);
-
-			proposal.flags[1] = true;
-			let mut did_pass = Self::should_pass(Proposals::<T>::get(proposal_id));
-			let tribute_offered = Self::u128_to_balance(proposal.tribute_offered);
-			let free_token_num = Self::balance_to_u128(T::Currency::free_balance(&Self::account_id()));
-			// too many tokens requested
-			if proposal.payment_requested > free_token_num {
-				did_pass = false;
+			Self::ensure_not_paused(CallCategory::Votes)?;
+			Self::do_submit_vote(dao_id, who, proposal_index, vote_unit, conviction)
+		}
+
+		/// Relay votes signed off-chain by the delegate keys of members, who then need no balance for the fees.
+		/// A vote with a bad nonce or signature is skipped, a vote which fails otherwise is skipped as well but
+		/// uses up its nonce. The guild bank refunds `RelayRefund` of the deposit token to the relayer for the first
+		/// counted vote of each member on a proposal, as far as its free balance affords it. On top of the benchmarked relay, every vote is charged the part of
+		/// `submit_vote` which grows with the queue
+		#[weight = T::WeightInfo::submit_votes_signed(votes.len() as u32).saturating_add(
+			T::WeightInfo::submit_vote(Module::<T>::proposal_queue_len(*dao_id))
+				.saturating_sub(T::WeightInfo::submit_vote(0))
+				.saturating_mul(votes.len() as Weight)
+		)]
+		#[transactional]
+		pub fn submit_votes_signed(origin, dao_id: DaoId, votes: Vec<SignedVoteOf<T>>) -> dispatch::DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Votes)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			let (mut counted, mut refunded) = (0u32, 0u128);
+			for signed_vote in votes.iter() {
+				let (is_counted, is_refunded) = Self::relay_vote(dao_id, signed_vote);
+				counted += is_counted as u32;
+				refunded += is_refunded as u128;
 			}
-			// shares+loot overflow
-			let total_requested = proposal.loot_requested.checked_add(proposal.shares_requested).unwrap();
-			let future_shares = TotalShares::get().checked_add(total_requested).unwrap();
-			ensure!(future_shares.checked_add(TotalLoot::get()).unwrap() <= T::MaxShares::get(), Error::<T>::SharesOverFlow);
-
-			// TODO: guild is full
-
-			// Proposal passed
-			if did_pass {
-				// mark did_pass to true
-				proposal.flags[2] = true;
-
-				// if the applicant is already a member, add to their existing shares
-				if Members::<T>::contains_key(&proposal.applicant) {
-					Members::<T>::mutate(&proposal.applicant, |mem| {
-						mem.shares = mem.shares.checked_add(proposal.shares_requested).unwrap();
-						mem.loot = mem.loot.checked_add(proposal.loot_requested).unwrap();
-					});
-				} else {
-					// if the applicant address is already taken by a member's delegateKey, reset it to their member address
-					if AddressOfDelegates::<T>::contains_key(proposal.applicant.clone()) {
-						let delegate = AddressOfDelegates::<T>::get(proposal.applicant.clone());
-						Members::<T>::mutate(delegate.clone(), |mem| {
-							mem.delegate_key = delegate.clone();
-						});
-						AddressOfDelegates::<T>::insert(delegate.clone(), delegate.clone());
-					}
-					// add new member
-					let member = Member {
-						shares: proposal.shares_requested,
-						highest_index_yes_vote: 0,
-						loot: proposal.loot_requested,
-						jailed_at: 0,
-						exists: true,
-						delegate_key: proposal.applicant.clone(),
-					};
-					Members::<T>::insert(proposal.applicant.clone(), member);
-					AddressOfDelegates::<T>::insert(proposal.applicant.clone(), proposal.applicant.clone());
-				}
 
-				// mint new shares
-				let totoal_shares = TotalShares::get().checked_add(proposal.shares_requested).unwrap();
-				TotalShares::put(totoal_shares);
-				// transfer correponding balance from custody account to guild bank's free balance
-				let res = T::Currency::transfer(&Self::custody_account(),  &Self::account_id(), tribute_offered, AllowDeath);
-				debug::info!("asdsa---{:?}", res);
-			} else {
-				// Proposal failed
-				// return the balance of applicant
-				let _ = T::Currency::transfer(&Self::custody_account(),  &proposal.applicant, tribute_offered, AllowDeath);
+			let deposit_token = Self::deposit_token(dao_id);
+			let refund = Self::balance_to_u128(RelayRefund::<T>::get(dao_id))
+				.saturating_mul(refunded)
+				.min(Self::free_guild_bank_balance(dao_id, deposit_token));
+			if refund > 0 {
+				Self::internal_transfer(dao_id, &Self::account_id(dao_id), &relayer, deposit_token, refund)?;
 			}
+			Self::deposit_event(RawEvent::SignedVotesRelayed(dao_id, relayer, counted, refund));
+			Ok(())
+		}
 
-			// need to mutate for update
-			Proposals::<T>::insert(proposal_id, proposal.clone());
-
-			// send reward
-			let _ = T::Currency::transfer(&Self::account_id(), &who, ProcessingReward::<T>::get(), KeepAlive);
-			// return deposit with reward slashed
-			let rest_balance = ProposalDeposit::<T>::get() - ProcessingReward::<T>::get();
-			let _ = T::Currency::transfer(&Self::account_id(), &proposal.proposer, rest_balance, KeepAlive);			
-
-			Self::deposit_event(RawEvent::ProcessProposal(
-				proposal_index, 
-				proposal.applicant.clone(),
-				proposal.proposer.clone(),
-				proposal.tribute_offered,
-				proposal.shares_requested,
-				did_pass
-			));
+		/// Withdraw the vote of a member while the voting period is open
+		#[weight = T::WeightInfo::retract_vote(Module::<T>::proposal_queue_len(*dao_id))]
+		pub fn retract_vote(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Votes)?;
+			let (delegate, proposal_id, _) = Self::open_for_voting(dao_id, &who, proposal_index)?;
+			ensure!(ProposalVotes::<T>::contains_key((dao_id, proposal_id), delegate.clone()), Error::<T>::MemberNotVoted);
+			Self::take_back_vote(dao_id, &delegate, proposal_index, proposal_id)?;
+			Self::deposit_event(RawEvent::VoteRetracted(dao_id, proposal_index, who, delegate));
 			Ok(())
 		}
 
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn process_guild_kick_proposal(origin, proposal_index: u128) -> dispatch::DispatchResult {
+		/// Process a proposal in queue
+		#[weight = T::WeightInfo::process_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn process_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			let proposal_len = ProposalQueue::get().len();
-			ensure!(proposal_index < proposal_len.try_into().unwrap(), Error::<T>::ProposalNotExist);
-			let _usize_proposal_index = TryInto::<usize>::try_into(proposal_index).ok().unwrap();
-			let proposal_id = ProposalQueue::get()[_usize_proposal_index];
-			let proposal = &mut Proposals::<T>::get(proposal_id);
-			// ensure guild kick proposal
-			ensure!(proposal.flags[5], Error::<T>::NotKickProposal);
-			ensure!(
-				Self::get_current_period() - VotingPeriodLength::get() - GracePeriodLength::get() >= proposal.starting_period,
-				Error::<T>::ProposalNotReady
-			);
-			ensure!(proposal.flags[1] == false, Error::<T>::ProposalHasProcessed);
-			ensure!(proposal_index == 0 || Proposals::<T>::get(ProposalQueue::get()[_usize_proposal_index - 1]).flags[1],
-			        Error::<T>::PreviousProposalNotProcessed);
-
-			proposal.flags[1] = true;
-			let did_pass = Self::should_pass(Proposals::<T>::get(proposal_id));
-			if did_pass {
-				// mark did_pass to true
-				proposal.flags[2] = true;
-				// update memeber status, i.e. jailed and slash shares
-				Members::<T>::mutate(proposal.applicant.clone(), |member| {
-					member.jailed_at = proposal_index;
-					member.loot = member.loot.checked_add(member.shares).unwrap();
-					let total_shares = TotalShares::get().checked_sub(member.shares).unwrap();
-					let total_loot = TotalLoot::get().checked_add(member.shares).unwrap();
-					TotalLoot::put(total_loot);
-					TotalShares::put(total_shares);
-					member.shares = 0;
-				});
-			}
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_proposal(dao_id, proposal_index, &who)
+		}
 
-			ProsedToKick::<T>::insert(proposal.applicant.clone(), false);
+		#[weight = T::WeightInfo::process_guild_kick_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn process_guild_kick_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_guild_kick_proposal(dao_id, proposal_index, &who)
+		}
 
-			// send reward
-			let _ = T::Currency::transfer(&Self::account_id(), &who, ProcessingReward::<T>::get(), KeepAlive);
-			// return deposit with reward slashed
-			let rest_balance = ProposalDeposit::<T>::get() - ProcessingReward::<T>::get();
-			let _ = T::Currency::transfer(&Self::account_id(), &proposal.proposer, rest_balance, KeepAlive);			
+		#[weight = T::WeightInfo::process_whitelist_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn process_whitelist_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_whitelist_proposal(dao_id, proposal_index, &who)
+		}
 
-			Ok(())
+		/// Process a cancel stream proposal in queue
+		#[weight = T::WeightInfo::process_cancel_stream_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn process_cancel_stream_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_cancel_stream_proposal(dao_id, proposal_index, &who)
+		}
+
+		/// Process a parameter change proposal in queue
+		#[weight = T::WeightInfo::process_parameter_proposal(Module::<T>::proposal_queue_len(*dao_id))]
+		#[transactional]
+		pub fn process_parameter_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_parameter_proposal(dao_id, proposal_index, &who)
+		}
+
+		/// Process an action proposal in queue, its call is dispatched if it passes
+		#[weight = T::WeightInfo::process_action_proposal(Module::<T>::proposal_queue_len(*dao_id)).saturating_add(T::MaxActionWeight::get())]
+		#[transactional]
+		pub fn process_action_proposal(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			Self::do_process_action_proposal(dao_id, proposal_index, &who)
 		}
 
 		/// proposer abort a proposal
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn abort(origin, proposal_index: u128) -> dispatch::DispatchResult {
+		#[weight = T::WeightInfo::abort()]
+		#[transactional]
+		pub fn abort(origin, dao_id: DaoId, proposal_index: u128) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			ensure!(Proposals::<T>::contains_key(proposal_index), Error::<T>::ProposalNotExist);
-			let proposal = &mut Proposals::<T>::get(proposal_index);
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			ensure!(Proposals::<T>::contains_key(dao_id, proposal_index), Error::<T>::ProposalNotExist);
+			let proposal = &mut Proposals::<T>::get(dao_id, proposal_index);
 			ensure!(who == proposal.proposer, Error::<T>::NotProposalProposer);
-			ensure!(!proposal.flags[0], Error::<T>::ProposalHasSponsored);
-			ensure!(!proposal.flags[3], Error::<T>::ProposalHasAborted);
-			let token_to_abort = proposal.tribute_offered;
+			ensure!(!proposal.is_sponsored(), Error::<T>::ProposalHasSponsored);
+			ensure!(!proposal.is_cancelled(), Error::<T>::ProposalHasAborted);
 			proposal.tribute_offered = 0;
-			proposal.flags[3] = true;
+			proposal.status = ProposalStatus::Cancelled;
 
 			// need to mutate for update
-			Proposals::<T>::insert(proposal_index, proposal.clone());
-			// return the token to applicant and delete record
-			let _ = T::Currency::transfer(&Self::custody_account(),  &proposal.proposer, Self::u128_to_balance(token_to_abort), AllowDeath);
-
-			Self::deposit_event(RawEvent::Abort(proposal_index, who.clone()));
+			Proposals::<T>::insert(dao_id, proposal_index, proposal.clone());
+			// return the token to whoever paid it and delete record
+			Self::release_tribute(dao_id, proposal_index, false)?;
+			ProposalEscrows::<T>::remove(dao_id, proposal_index);
+			ProposalActions::<T>::remove(dao_id, proposal_index);
+			ProposalVestings::remove(dao_id, proposal_index);
+			ProposalStreamCancels::remove(dao_id, proposal_index);
+			ProposalParameterChanges::<T>::remove(dao_id, proposal_index);
+
+			Self::deposit_event(RawEvent::Abort(dao_id, proposal_index, who.clone()));
 			Ok(())
 		}
 
 		/// Member rage quit
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn rage_quit(origin, shares_to_burn: u128, loot_to_burn: u128) -> dispatch::DispatchResult {
+		#[weight = T::WeightInfo::rage_quit(Module::<T>::proposal_queue_len(*dao_id), T::MaxTokens::get())]
+		#[transactional]
+		pub fn rage_quit(origin, dao_id: DaoId, shares_to_burn: u128, loot_to_burn: u128) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
-			Self::member_quit(who, shares_to_burn, loot_to_burn)
+			Self::member_quit(dao_id, who, shares_to_burn, loot_to_burn)
 		}
 
 		/// kick anymember  in jail
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn rage_kick(origin, member_to_kick: T::AccountId) -> dispatch::DispatchResult {
+		#[weight = T::WeightInfo::rage_kick(Module::<T>::proposal_queue_len(*dao_id), T::MaxTokens::get())]
+		#[transactional]
+		pub fn rage_kick(origin, dao_id: DaoId, member_to_kick: T::AccountId) -> dispatch::DispatchResult {
 			let _ = ensure_signed(origin)?;
-			let member = Members::<T>::get(member_to_kick.clone());
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			let member = Members::<T>::get(dao_id, member_to_kick.clone());
 			ensure!(member.jailed_at != 0, Error::<T>::MemberNotInJail);
 			ensure!(member.loot > 0, Error::<T>::NoEnoughLoot);
-			Self::member_quit(member_to_kick, 0, member.loot)
+			Self::member_quit(dao_id, member_to_kick, 0, member.loot)
+		}
+
+		/// Withdraw tokens from the caller's internal balance
+		#[weight = T::WeightInfo::withdraw_balance()]
+		#[transactional]
+		pub fn withdraw_balance(origin, dao_id: DaoId, token: T::CurrencyId, #[compact] amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::withdraw(dao_id, &who, token, Self::balance_to_u128(amount))
+		}
+
+		/// Withdraw several tokens at once, withdraw the whole balance of each token if `max` is set
+		#[weight = T::WeightInfo::withdraw_balances(tokens.len() as u32)]
+		#[transactional]
+		pub fn withdraw_balances(origin, dao_id: DaoId, tokens: Vec<T::CurrencyId>, amounts: Vec<BalanceOf<T>>, max: bool) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(tokens.len() == amounts.len(), Error::<T>::InvalidWithdrawal);
+			for (token, amount) in tokens.into_iter().zip(amounts.into_iter()) {
+				let amount = match max {
+					true => UserTokenBalances::<T>::get((dao_id, who.clone()), token),
+					false => Self::balance_to_u128(amount),
+				};
+				Self::withdraw(dao_id, &who, token, amount)?;
+			}
+			Ok(())
 		}
 
 		/// update the delegate
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn update_delegate(origin, delegate_key: T::AccountId) -> dispatch::DispatchResult {
+		#[weight = T::WeightInfo::update_delegate()]
+		pub fn update_delegate(origin, dao_id: DaoId, delegate_key: T::AccountId) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Votes)?;
 			// skip checks if member is setting the delegate key to their member address
 			if who != delegate_key {
-				ensure!(!Members::<T>::contains_key(delegate_key.clone()), Error::<T>::NoOverwriteMember);
-				let delegate = AddressOfDelegates::<T>::get(delegate_key.clone());
-				ensure!(!Members::<T>::contains_key(delegate.clone()), Error::<T>::NoOverwriteDelegate);
+				ensure!(!Members::<T>::contains_key(dao_id, delegate_key.clone()), Error::<T>::NoOverwriteMember);
+				let delegate = AddressOfDelegates::<T>::get(dao_id, delegate_key.clone());
+				ensure!(!Members::<T>::contains_key(dao_id, delegate.clone()), Error::<T>::NoOverwriteDelegate);
 			}
 
-			let member = &mut Members::<T>::get(who.clone());
-			AddressOfDelegates::<T>::remove(member.delegate_key.clone());
-			AddressOfDelegates::<T>::insert(delegate_key.clone(), who.clone());
+			let member = &mut Members::<T>::get(dao_id, who.clone());
+			AddressOfDelegates::<T>::remove(dao_id, member.delegate_key.clone());
+			AddressOfDelegates::<T>::insert(dao_id, delegate_key.clone(), who.clone());
 			member.delegate_key = delegate_key.clone();
-			Self::deposit_event(RawEvent::UpdateDelegateKey(who, delegate_key));
+			Self::deposit_event(RawEvent::UpdateDelegateKey(dao_id, who, delegate_key));
+			Ok(())
+		}
+
+		/// Set the quorum and approval threshold of a proposal kind, as parts of the total shares of the DAO.
+		/// They apply to proposals of that kind processed from now on, including those already voted
+		#[weight = T::WeightInfo::set_voting_threshold()]
+		pub fn set_voting_threshold(origin, dao_id: DaoId, kind: ProposalKind, quorum: Perbill, approval: Perbill) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			VotingThresholds::insert(dao_id, kind, VotingThreshold { quorum, approval });
+			Self::deposit_event(RawEvent::VotingThresholdSet(dao_id, kind, quorum, approval));
+			Ok(())
+		}
+
+		/// The admin origin sets how the votes on proposals of a DAO are weighted, proposals already sponsored keep their mode
+		#[weight = T::WeightInfo::set_voting_mode()]
+		pub fn set_voting_mode(origin, dao_id: DaoId, mode: VotingMode) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			VotingModes::insert(dao_id, mode);
+			Self::deposit_event(RawEvent::VotingModeSet(dao_id, mode));
+			Ok(())
+		}
+
+		/// The admin origin sets the deposit tokens the guild bank refunds to the relayer of each signed vote
+		#[weight = T::WeightInfo::set_relay_refund()]
+		pub fn set_relay_refund(origin, dao_id: DaoId, #[compact] refund: BalanceOf<T>) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+			RelayRefund::<T>::insert(dao_id, refund);
+			Self::deposit_event(RawEvent::RelayRefundSet(dao_id, refund));
+			Ok(())
+		}
+
+		/// The admin origin pauses a category of calls of every DAO until the block `until`, or until `unpause` if it is none.
+		/// `rage_quit` and withdrawing stay open
+		#[weight = T::WeightInfo::pause()]
+		pub fn pause(origin, category: CallCategory, until: Option<T::BlockNumber>) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Pauses::<T>::insert(category, until.unwrap_or_else(T::BlockNumber::max_value));
+			Self::deposit_event(RawEvent::CallsPaused(category, until));
+			Ok(())
+		}
+
+		/// The admin origin lifts the pause of a category of calls before it expires
+		#[weight = T::WeightInfo::unpause()]
+		pub fn unpause(origin, category: CallCategory) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Pauses::<T>::remove(category);
+			Self::deposit_event(RawEvent::CallsUnpaused(category));
 			Ok(())
 		}
 	}
@@ -608,14 +1340,276 @@ impl<T: Config> Module<T> {
 
 	/// refer https://github.com/paritytech/substrate/blob/743accbe3256de2fc615adcaa3ab03ebdbbb4dbd/frame/treasury/src/lib.rs#L351
 	///
+	/// The guild bank of a DAO, every DAO owns a sub account derived from the module id.
 	/// This actually does computation. If you need to keep using it, then make sure you cache the
 	/// value and only call this once.
-	pub fn account_id() -> T::AccountId {
-		T::ModuleId::get().into_account()
+	pub fn account_id(dao_id: DaoId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(dao_id)
+	}
+
+	pub fn custody_account(dao_id: DaoId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(("custody", dao_id))
+	}
+
+	/// The origin of the calls of action proposals, apart from the guild bank so that an action can not spend
+	/// the tokens kept for the internal balances
+	pub fn action_account(dao_id: DaoId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account(("action", dao_id))
+	}
+
+	pub fn dao_exists(dao_id: DaoId) -> bool {
+		dao_id < DaoCount::get()
+	}
+
+	/// Length of the proposal queue without decoding it, used to weigh the extrinsics which read the whole queue
+	pub fn proposal_queue_len(dao_id: DaoId) -> u32 {
+		ProposalQueue::decode_len(dao_id).unwrap_or_default() as u32
+	}
+
+	/// The token used for proposal deposits and processing rewards, i.e. the first whitelisted token
+	pub fn deposit_token(dao_id: DaoId) -> T::CurrencyId {
+		ApprovedTokens::<T>::get(dao_id).first().cloned().unwrap_or_default()
 	}
 
-	pub fn custody_account() -> T::AccountId {
-		T::ModuleId::get().into_sub_account("custody")
+	/// The amount of `token` in the guild bank, including the unclaimed payments of payout streams
+	pub fn guild_bank_balance(dao_id: DaoId, token: T::CurrencyId) -> u128 {
+		UserTokenBalances::<T>::get((dao_id, Self::account_id(dao_id)), token)
+	}
+
+	/// The amount of `token` in the guild bank which can be paid out, the payout streams are committed
+	pub fn free_guild_bank_balance(dao_id: DaoId, token: T::CurrencyId) -> u128 {
+		Self::guild_bank_balance(dao_id, token).saturating_sub(StreamCommitments::<T>::get(dao_id, token))
+	}
+
+	/// All the internal balances of an account in a DAO
+	pub fn user_balances(dao_id: DaoId, who: T::AccountId) -> Vec<(T::CurrencyId, u128)> {
+		UserTokenBalances::<T>::iter_prefix((dao_id, who)).collect()
+	}
+
+	/// The guild bank balance of every whitelisted token, in whitelist order
+	pub fn guild_bank_balances(dao_id: DaoId) -> Vec<(T::CurrencyId, u128)> {
+		ApprovedTokens::<T>::get(dao_id).into_iter()
+			.map(|token| (token, Self::guild_bank_balance(dao_id, token)))
+			.collect()
+	}
+
+	/// A member of a DAO, `None` if `who` has never been a member
+	pub fn member(dao_id: DaoId, who: T::AccountId) -> Option<MemberOf<T>> {
+		if Members::<T>::contains_key(dao_id, who.clone()) {
+			Some(Members::<T>::get(dao_id, who))
+		} else {
+			None
+		}
+	}
+
+	/// Proposals with ids in `[start, start + count)` along with their status, stops at the last proposal
+	pub fn proposals_page(dao_id: DaoId, start: u128, count: u32) -> Vec<(u128, ProposalOf<T>, ProposalStatus)> {
+		let end = start.saturating_add(count.into()).min(ProposalCount::get(dao_id));
+		(start..end)
+			.map(|id| (id, Proposals::<T>::get(dao_id, id), Self::proposal_status(dao_id, id)))
+			.collect()
+	}
+
+	/// The vote of a member on a proposal, 1 for yes, 2 for no and 3 to abstain, votes are recorded against the member not the delegate
+	pub fn member_vote(dao_id: DaoId, proposal_id: u128, who: T::AccountId) -> Option<u8> {
+		if ProposalVotes::<T>::contains_key((dao_id, proposal_id), who.clone()) {
+			Some(ProposalVotes::<T>::get((dao_id, proposal_id), who))
+		} else {
+			None
+		}
+	}
+
+	/// The current period of a DAO, `None` if it is not summoned yet
+	pub fn current_period(dao_id: DaoId) -> Option<u128> {
+		if Self::dao_exists(dao_id) {
+			Some(Self::get_current_period(dao_id))
+		} else {
+			None
+		}
+	}
+
+	/// Credit `amount` of `token` to the internal balance of `who`
+	pub fn add_to_balance(dao_id: DaoId, who: &T::AccountId, token: T::CurrencyId, amount: u128) -> dispatch::DispatchResult {
+		UserTokenBalances::<T>::try_mutate((dao_id, who.clone()), token, |balance| -> dispatch::DispatchResult {
+			*balance = balance.checked_add(amount).ok_or(Error::<T>::StorageOverflow)?;
+			Ok(())
+		})
+	}
+
+	/// Debit `amount` of `token` from the internal balance of `who`
+	pub fn subtract_from_balance(dao_id: DaoId, who: &T::AccountId, token: T::CurrencyId, amount: u128) -> dispatch::DispatchResult {
+		UserTokenBalances::<T>::try_mutate((dao_id, who.clone()), token, |balance| -> dispatch::DispatchResult {
+			*balance = balance.checked_sub(amount).ok_or(Error::<T>::InsufficientBalance)?;
+			Ok(())
+		})
+	}
+
+	/// Move internal balance between accounts, no token leaves the DAO
+	pub fn internal_transfer(dao_id: DaoId, from: &T::AccountId, to: &T::AccountId, token: T::CurrencyId, amount: u128) -> dispatch::DispatchResult {
+		Self::subtract_from_balance(dao_id, from, token, amount)?;
+		Self::add_to_balance(dao_id, to, token, amount)
+	}
+
+	/// Pay out internal balance, the ledger is only updated once the transfer succeeded
+	pub fn withdraw(dao_id: DaoId, who: &T::AccountId, token: T::CurrencyId, amount: u128) -> dispatch::DispatchResult {
+		ensure!(UserTokenBalances::<T>::get((dao_id, who.clone()), token) >= amount, Error::<T>::InsufficientBalance);
+		T::Currency::transfer(token, &Self::account_id(dao_id), who, Self::u128_to_balance(amount))?;
+		Self::subtract_from_balance(dao_id, who, token, amount)?;
+		Self::deposit_event(RawEvent::Withdraw(dao_id, who.clone(), token, amount));
+		Ok(())
+	}
+
+	/// Move the escrowed tribute of a proposal into the guild bank, or refund it to the payer
+	pub fn release_tribute(dao_id: DaoId, proposal_id: u128, to_guild_bank: bool) -> dispatch::DispatchResult {
+		let escrow = ProposalEscrows::<T>::get(dao_id, proposal_id);
+		let receiver = match to_guild_bank {
+			true => Self::account_id(dao_id),
+			false => escrow.tribute_payer.clone(),
+		};
+		Self::internal_transfer(dao_id, &Self::custody_account(dao_id), &receiver, escrow.tribute_token, escrow.tribute)?;
+		ProposalEscrows::<T>::mutate(dao_id, proposal_id, |e| e.tribute = 0);
+		Ok(())
+	}
+
+	/// Credit the processing reward in the escrow to the processor and return the rest of the deposit to the sponsor who paid it,
+	/// the escrow of the proposal is cleared
+	pub fn pay_processing_reward(dao_id: DaoId, proposal_id: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let escrow = ProposalEscrows::<T>::take(dao_id, proposal_id);
+		// send reward
+		Self::internal_transfer(dao_id, &Self::custody_account(dao_id), processor, escrow.deposit_token, escrow.processing_reward)?;
+		// return deposit with reward slashed
+		let rest_balance = escrow.deposit - escrow.processing_reward;
+		Self::internal_transfer(dao_id, &Self::custody_account(dao_id), &escrow.deposit_payer, escrow.deposit_token, rest_balance)
+	}
+
+	/// The processing reward of a proposal as (token, amount), fixed with the deposit at sponsoring time
+	pub fn processing_reward_of(dao_id: DaoId, proposal_id: u128) -> (T::CurrencyId, u128) {
+		let escrow = ProposalEscrows::<T>::get(dao_id, proposal_id);
+		(escrow.deposit_token, escrow.processing_reward)
+	}
+
+	pub fn validate_summon(
+		summoners: &[(T::AccountId, u128, u128)],
+		approved_tokens: &[T::CurrencyId],
+		period_duration: u32,
+		voting_period_length: u128,
+		grace_period_length: u128,
+		dilution_bound: u128,
+		proposal_deposit: BalanceOf<T>,
+		processing_reward: BalanceOf<T>
+	) -> dispatch::DispatchResult {
+		Self::validate_parameters(period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward)?;
+		ensure!(!summoners.is_empty(), Error::<T>::NoSummoner);
+
+		let mut accounts: Vec<T::AccountId> = summoners.iter().map(|(account, _, _)| account.clone()).collect();
+		accounts.sort();
+		accounts.dedup();
+		ensure!(accounts.len() == summoners.len(), Error::<T>::DuplicateSummoner);
+
+		ensure!(!approved_tokens.is_empty(), Error::<T>::NoApprovedToken);
+		ensure!(approved_tokens.len() <= T::MaxTokens::get() as usize, Error::<T>::TooManyTokens);
+		let mut tokens = approved_tokens.to_vec();
+		tokens.sort();
+		tokens.dedup();
+		ensure!(tokens.len() == approved_tokens.len(), Error::<T>::DuplicateToken);
+
+		let mut total_shares: u128 = 0;
+		let mut total_loot: u128 = 0;
+		for (_, shares, loot) in summoners.iter() {
+			ensure!(*shares > 0 || *loot > 0, Error::<T>::NoEnoughShares);
+			total_shares = total_shares.checked_add(*shares).ok_or(Error::<T>::SharesOverFlow)?;
+			total_loot = total_loot.checked_add(*loot).ok_or(Error::<T>::SharesOverFlow)?;
+		}
+		// at least one summoner should be able to vote
+		ensure!(total_shares > 0, Error::<T>::NoEnoughShares);
+		let total = total_shares.checked_add(total_loot).ok_or(Error::<T>::SharesOverFlow)?;
+		ensure!(total <= T::MaxShares::get(), Error::<T>::SharesOverFlow);
+		Ok(())
+	}
+
+	/// Check the settings of a DAO against the limits of the runtime
+	pub fn validate_parameters(
+		period_duration: u32,
+		voting_period_length: u128,
+		grace_period_length: u128,
+		dilution_bound: u128,
+		proposal_deposit: BalanceOf<T>,
+		processing_reward: BalanceOf<T>
+	) -> dispatch::DispatchResult {
+		ensure!(period_duration > 0, Error::<T>::PeriodDurationIsZero);
+		ensure!(voting_period_length <= T::MaxVotingPeriodLength::get(), Error::<T>::VotingPeriodLengthTooBig);
+		ensure!(grace_period_length <= T::MaxGracePeriodLength::get(), Error::<T>::GracePeriodLengthTooBig);
+		ensure!(dilution_bound <= T::MaxDilutionBound::get(), Error::<T>::DilutionBoundTooBig);
+		ensure!(proposal_deposit >= processing_reward, Error::<T>::NoEnoughProposalDeposit);
+		Ok(())
+	}
+
+	/// Check the settings of a DAO after `change`
+	pub fn validate_parameter_change(dao_id: DaoId, change: &ParameterChangeOf<T>) -> dispatch::DispatchResult {
+		Self::validate_parameters(
+			change.period_duration.unwrap_or_else(|| PeriodDuration::get(dao_id)),
+			change.voting_period_length.unwrap_or_else(|| VotingPeriodLength::get(dao_id)),
+			change.grace_period_length.unwrap_or_else(|| GracePeriodLength::get(dao_id)),
+			change.dilution_bound.unwrap_or_else(|| DilutionBound::get(dao_id)),
+			change.proposal_deposit.unwrap_or_else(|| ProposalDeposit::<T>::get(dao_id)),
+			change.processing_reward.unwrap_or_else(|| ProcessingReward::<T>::get(dao_id))
+		)
+	}
+
+	/// Register a new DAO with its founding members, the summoners should have been validated by `validate_summon`
+	pub fn do_summon(
+		summoners: Vec<(T::AccountId, u128, u128)>,
+		approved_tokens: Vec<T::CurrencyId>,
+		period_duration: u32,
+		voting_period_length: u128,
+		grace_period_length: u128,
+		dilution_bound: u128,
+		proposal_deposit: BalanceOf<T>,
+		processing_reward: BalanceOf<T>
+	) -> Result<DaoId, DispatchError> {
+		let dao_id = DaoCount::get();
+		ensure!(!SummonTime::contains_key(dao_id), Error::<T>::DaoAlreadySummoned);
+		let next_dao_id = dao_id.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+
+		SummonTime::insert(dao_id, T::Clock::now());
+		PeriodDuration::insert(dao_id, period_duration);
+		VotingPeriodLength::insert(dao_id, voting_period_length);
+		GracePeriodLength::insert(dao_id, grace_period_length);
+		DilutionBound::insert(dao_id, dilution_bound);
+
+		ProposalDeposit::<T>::insert(dao_id, proposal_deposit);
+		ProcessingReward::<T>::insert(dao_id, processing_reward);
+
+		for token in approved_tokens.iter() {
+			TokenWhitelist::<T>::insert(dao_id, token, true);
+		}
+		ApprovedTokens::<T>::insert(dao_id, approved_tokens);
+
+		let mut total_shares: u128 = 0;
+		let mut total_loot: u128 = 0;
+		let accounts: Vec<T::AccountId> = summoners.iter().map(|(summoner, _, _)| summoner.clone()).collect();
+		for (summoner, shares, loot) in summoners.into_iter() {
+			let member = Member {
+				shares: shares,
+				highest_index_yes_vote: 0,
+				loot: loot,
+				jailed_at: 0,
+				exists: true,
+				delegate_key: summoner.clone(),
+			};
+			Members::<T>::insert(dao_id, summoner.clone(), member);
+			AddressOfDelegates::<T>::insert(dao_id, summoner.clone(), summoner.clone());
+			total_shares = total_shares.checked_add(shares).ok_or(Error::<T>::SharesOverFlow)?;
+			total_loot = total_loot.checked_add(loot).ok_or(Error::<T>::SharesOverFlow)?;
+			Self::deposit_event(RawEvent::SummonComplete(dao_id, summoner, shares));
+		}
+		TotalShares::insert(dao_id, total_shares);
+		TotalLoot::insert(dao_id, total_loot);
+		for summoner in accounts.iter() {
+			Self::checkpoint_shares(dao_id, summoner);
+		}
+		DaoCount::put(next_dao_id);
+		Ok(dao_id)
 	}
 
 	pub fn u128_to_balance(cost: u128) -> BalanceOf<T> {
@@ -626,90 +1620,855 @@ impl<T: Config> Module<T> {
 		TryInto::<u128>::try_into(balance).ok().unwrap()
 	}
 
-	pub fn get_current_period() -> u128 {
-		let now = TryInto::<u128>::try_into(pallet_timestamp::Module::<T>::now()).ok().unwrap();
-		let summon_time = TryInto::<u128>::try_into(SummonTime::<T>::get()).ok().unwrap();
-		let diff = now.checked_sub(summon_time).unwrap();
-		// the timestamp is in milli seconds
-		diff.checked_div(1000).unwrap().checked_div(PeriodDuration::get().into()).unwrap()
+	/// Whether the calls of `category` are paused, by their own pause or by pausing all calls
+	pub fn is_paused(category: CallCategory) -> bool {
+		let now = frame_system::Module::<T>::block_number();
+		[CallCategory::All, category].iter().any(|category| Pauses::<T>::get(category).map_or(false, |until| now < until))
+	}
+
+	/// Fails with `CallsArePaused` while `category` is paused
+	pub fn ensure_not_paused(category: CallCategory) -> dispatch::DispatchResult {
+		ensure!(!Self::is_paused(category), Error::<T>::CallsArePaused);
+		Ok(())
+	}
+
+	/// Periods elapsed since the DAO was summoned, a clock behind the summon time counts as period 0
+	pub fn get_current_period(dao_id: DaoId) -> u128 {
+		let (anchor_time, anchor_period) = PeriodAnchor::get(dao_id).unwrap_or_else(|| (SummonTime::get(dao_id), 0));
+		let elapsed = T::Clock::now().saturating_sub(anchor_time);
+		anchor_period.saturating_add(elapsed.checked_div(PeriodDuration::get(dao_id).into()).unwrap_or_default())
+	}
+
+	/// Submit a standard proposal, returns the id of the proposal
+	pub fn do_submit_proposal(
+		dao_id: DaoId,
+		who: T::AccountId,
+		applicant: T::AccountId,
+		tribute_offered: u128,
+		tribute_token: T::CurrencyId,
+		shares_requested: u128,
+		loot_requested: u128,
+		payment_requested: u128,
+		payment_token: T::CurrencyId,
+		details: Vec<u8>
+	) -> Result<u128, DispatchError> {
+		ensure!(Self::dao_exists(dao_id), Error::<T>::DaoNotExist);
+		ensure!(TokenWhitelist::<T>::get(dao_id, tribute_token), Error::<T>::TokenNotWhitelisted);
+		ensure!(TokenWhitelist::<T>::get(dao_id, payment_token), Error::<T>::TokenNotWhitelisted);
+		if Members::<T>::contains_key(dao_id, who.clone()) {
+			ensure!(Members::<T>::get(dao_id, who.clone()).jailed_at == 0, Error::<T>::MemberInJail);
+		}
+		let total_requested = loot_requested.checked_add(shares_requested).ok_or(Error::<T>::SharesOverFlow)?;
+		let future_shares = TotalShares::get(dao_id).checked_add(total_requested).ok_or(Error::<T>::SharesOverFlow)?;
+		ensure!(future_shares <= T::MaxShares::get(), Error::<T>::SharesOverFlow);
+
+		// collect tribute from proposer and store it in the escrow until the proposal is processed
+		T::Currency::transfer(tribute_token, &who, &Self::account_id(dao_id), Self::u128_to_balance(tribute_offered))?;
+		Self::add_to_balance(dao_id, &Self::custody_account(dao_id), tribute_token, tribute_offered)?;
+
+		let proposal_index = ProposalCount::get(dao_id);
+		Self::create_proposal(dao_id, who, applicant, shares_requested, loot_requested,
+		                      tribute_offered, tribute_token, payment_requested, payment_token, details, ProposalKind::Standard);
+		Ok(proposal_index)
 	}
 
 	pub fn create_proposal(
+		dao_id: DaoId,
 		proposer: T::AccountId,
 		applicant: T::AccountId,
 		shares_requested: u128,
 		loot_requested: u128,
 		tribute_offered: u128,
+		tribute_token: T::CurrencyId,
 		payment_requested: u128,
+		payment_token: T::CurrencyId,
 		details: Vec<u8>,
-		flags: [bool; 6]
+		kind: ProposalKind
 	) {
-			let proposal_index = ProposalCount::get();
+			let proposal_index = ProposalCount::get(dao_id);
 			let proposal = Proposal {
 				proposer: proposer.clone(),
 				applicant: applicant.clone(),
 				sponsor: proposer.clone(),
 				shares_requested: shares_requested,
 				starting_period: 0,
+				voting_period_length: 0,
+				grace_period_length: 0,
+				voting_mode: VotingMode::Linear,
 				yes_votes: 0,
 				no_votes: 0,
+				yes_shares: 0,
+				no_shares: 0,
 				details: details,
 				max_total_shares_at_yes: 0,
 				loot_requested: loot_requested,
 				tribute_offered: tribute_offered,
+				tribute_token: tribute_token,
 				payment_requested: payment_requested,
-				flags: flags
+				payment_token: payment_token,
+				kind: kind,
+				status: ProposalStatus::Submitted,
 			};
-			Proposals::<T>::insert(proposal_index, proposal);
-			Self::deposit_event(RawEvent::SubmitProposal(proposal_index, proposer.clone(), proposer, applicant, tribute_offered, shares_requested));	
-			ProposalCount::put(proposal_index + 1);
+			Proposals::<T>::insert(dao_id, proposal_index, proposal);
+			ProposalEscrows::<T>::insert(dao_id, proposal_index, Escrow {
+				tribute_payer: proposer.clone(),
+				tribute_token: tribute_token,
+				tribute: tribute_offered,
+				deposit_payer: proposer.clone(),
+				deposit_token: tribute_token,
+				deposit: 0,
+				processing_reward: 0,
+			});
+			Self::deposit_event(RawEvent::SubmitProposal(dao_id, proposal_index, proposer.clone(), proposer, applicant, tribute_offered, shares_requested));	
+			ProposalCount::insert(dao_id, proposal_index + 1);
+	}
+
+	/// The status of a proposal, Voting, Grace and ReadyToProcess are derived from the current period
+	pub fn proposal_status(dao_id: DaoId, proposal_id: u128) -> ProposalStatus {
+		let proposal = Proposals::<T>::get(dao_id, proposal_id);
+		match proposal.status {
+			ProposalStatus::Sponsored => {
+				let current_period = Self::get_current_period(dao_id);
+				if current_period < proposal.starting_period {
+					ProposalStatus::Sponsored
+				} else if current_period < proposal.voting_ends() {
+					ProposalStatus::Voting
+				} else if current_period < proposal.grace_ends() {
+					ProposalStatus::Grace
+				} else {
+					ProposalStatus::ReadyToProcess
+				}
+			},
+			status => status,
+		}
+	}
+
+	/// The member whose delegate key is `who` and the proposal at `proposal_index` of the queue, if its voting period is open
+	fn open_for_voting(dao_id: DaoId, who: &T::AccountId, proposal_index: u128) -> Result<(T::AccountId, u128, ProposalOf<T>), DispatchError> {
+		ensure!(AddressOfDelegates::<T>::contains_key(dao_id, who.clone()), Error::<T>::NotMember);
+		let delegate = AddressOfDelegates::<T>::get(dao_id, who.clone());
+		let proposal_id = *ProposalQueue::get(dao_id).get(proposal_index as usize).ok_or(Error::<T>::ProposalNotExist)?;
+		let proposal = Proposals::<T>::get(dao_id, proposal_id);
+		let current_period = Self::get_current_period(dao_id);
+		ensure!(current_period >= proposal.starting_period, Error::<T>::ProposalNotStart);
+		ensure!(current_period < proposal.voting_ends(), Error::<T>::ProposalExpired);
+		ensure!(!proposal.is_cancelled(), Error::<T>::ProposalHasAborted);
+		Ok((delegate, proposal_id, proposal))
+	}
+
+	/// Cast or change the vote of the member whose delegate key is `who`
+	pub fn do_submit_vote(dao_id: DaoId, who: T::AccountId, proposal_index: u128, vote_unit: u8, conviction: Conviction) -> dispatch::DispatchResult {
+		let (delegate, proposal_id, proposal) = Self::open_for_voting(dao_id, &who, proposal_index)?;
+		let vote = Vote::from_unit(vote_unit).ok_or(Error::<T>::InvalidVote)?;
+		// abstaining shares are not weighted
+		ensure!(vote != Vote::Null || conviction == Conviction::None, Error::<T>::InvalidVote);
+		let member = Members::<T>::get(dao_id, delegate.clone());
+		// weighted by the shares held when the voting started, less any burnt since then
+		let shares = Self::shares_at(dao_id, &delegate, proposal.starting_period).min(member.shares);
+		ensure!(shares > 0, Error::<T>::NoEnoughShares);
+		if ProposalVotes::<T>::contains_key((dao_id, proposal_id), delegate.clone()) {
+			ensure!(
+				ProposalVotes::<T>::get((dao_id, proposal_id), delegate.clone()) != vote_unit ||
+					VoteConvictions::<T>::get((dao_id, proposal_id), delegate.clone()) != conviction,
+				Error::<T>::MemberHasVoted
+			);
+			Self::take_back_vote(dao_id, &delegate, proposal_index, proposal_id)?;
+		}
+
+		ProposalVotes::<T>::insert((dao_id, proposal_id), delegate.clone(), vote_unit);
+		VoteWeights::<T>::insert((dao_id, proposal_id), delegate.clone(), shares);
+		let weight = proposal.voting_mode.vote_weight(shares).saturating_mul(conviction.multiplier());
+		if conviction != Conviction::None {
+			VoteConvictions::<T>::insert((dao_id, proposal_id), delegate.clone(), conviction);
+			let until = proposal.grace_ends().saturating_add(conviction.lock_periods().saturating_mul(proposal.voting_period_length));
+			let current_period = Self::get_current_period(dao_id);
+			ShareLocks::<T>::mutate(dao_id, delegate.clone(), |locks| {
+				locks.retain(|lock| lock.until > current_period);
+				locks.push(ShareLock { proposal_id, shares, until });
+			});
+		}
+		match vote {
+			Vote::Yes => {
+				Proposals::<T>::mutate(dao_id, proposal_id, |p| {
+					p.yes_votes = p.yes_votes.checked_add(weight).unwrap();
+					p.yes_shares = p.yes_shares.checked_add(shares).unwrap();
+					// update max yes
+					let all_loot_shares = TotalShares::get(dao_id).checked_add(TotalLoot::get(dao_id)).unwrap();
+					if all_loot_shares > p.max_total_shares_at_yes {
+						p.max_total_shares_at_yes = all_loot_shares;
+					}
+				});
+				// read again, taking back a changed yes vote lowers it
+				Members::<T>::mutate(dao_id, delegate.clone(), |mem| {
+					if proposal_index > mem.highest_index_yes_vote {
+						mem.highest_index_yes_vote = proposal_index;
+					}
+				});
+			},
+			Vote::No => Proposals::<T>::mutate(dao_id, proposal_id, |p| {
+				p.no_votes = p.no_votes.checked_add(weight).unwrap();
+				p.no_shares = p.no_shares.checked_add(shares).unwrap();
+			}),
+			Vote::Null => AbstainVotes::mutate(dao_id, proposal_id, |abstains| {
+				*abstains = abstains.checked_add(shares).unwrap();
+			}),
+		}
+		Self::deposit_event(RawEvent::SubmitVote(dao_id, proposal_index, who, delegate, vote_unit));
+		Ok(())
+	}
+
+	/// Count a vote relayed by `submit_votes_signed`, returns whether it is counted and whether it is refunded
+	fn relay_vote(dao_id: DaoId, signed_vote: &SignedVoteOf<T>) -> (bool, bool) {
+		let delegate_key = signed_vote.delegate_key.clone();
+		let nonce = signed_vote.nonce;
+		let message = VoteMessage {
+			dao_id,
+			proposal_index: signed_vote.proposal_index,
+			vote_unit: signed_vote.vote_unit,
+			nonce,
+		};
+		let checked = if nonce != VoteNonces::<T>::get(dao_id, delegate_key.clone()) {
+			Err(Error::<T>::BadVoteNonce.into())
+		} else if !signed_vote.signature.verify(&message.encode()[..], &delegate_key) {
+			Err(Error::<T>::BadVoteSignature.into())
+		} else {
+			// the nonce is used up even if the vote fails, so that it can not be replayed later
+			VoteNonces::<T>::insert(dao_id, delegate_key.clone(), nonce + 1);
+			Self::do_submit_vote(dao_id, delegate_key.clone(), signed_vote.proposal_index, signed_vote.vote_unit, Conviction::None)
+		};
+		if let Err(e) = checked {
+			Self::deposit_event(RawEvent::SignedVoteFailed(dao_id, delegate_key, nonce, e));
+			return (false, false);
+		}
+		// a changed vote is not refunded again, or flipping it would drain the guild bank
+		let member = AddressOfDelegates::<T>::get(dao_id, delegate_key);
+		let proposal_id = ProposalQueue::get(dao_id)[signed_vote.proposal_index as usize];
+		let refunded = RelayRefunded::<T>::get((dao_id, proposal_id), member.clone());
+		RelayRefunded::<T>::insert((dao_id, proposal_id), member, true);
+		(true, !refunded)
+	}
+
+	/// Remove a counted vote of a member, votes cast before their weight was recorded can not be taken back
+	fn take_back_vote(dao_id: DaoId, delegate: &T::AccountId, proposal_index: u128, proposal_id: u128) -> dispatch::DispatchResult {
+		ensure!(VoteWeights::<T>::contains_key((dao_id, proposal_id), delegate.clone()), Error::<T>::VoteNotChangeable);
+		let shares = VoteWeights::<T>::take((dao_id, proposal_id), delegate.clone());
+		let vote_unit = ProposalVotes::<T>::take((dao_id, proposal_id), delegate.clone());
+		let conviction = VoteConvictions::<T>::take((dao_id, proposal_id), delegate.clone());
+		let weight = Proposals::<T>::get(dao_id, proposal_id).voting_mode.vote_weight(shares).saturating_mul(conviction.multiplier());
+		if conviction != Conviction::None {
+			ShareLocks::<T>::mutate(dao_id, delegate.clone(), |locks| locks.retain(|lock| lock.proposal_id != proposal_id));
+		}
+		match Vote::from_unit(vote_unit) {
+			Some(Vote::Yes) => {
+				Proposals::<T>::mutate(dao_id, proposal_id, |p| {
+					p.yes_votes = p.yes_votes.saturating_sub(weight);
+					p.yes_shares = p.yes_shares.saturating_sub(shares);
+				});
+				if Members::<T>::get(dao_id, delegate.clone()).highest_index_yes_vote == proposal_index {
+					let previous = Self::previous_yes_vote(dao_id, delegate, proposal_index);
+					Members::<T>::mutate(dao_id, delegate.clone(), |mem| mem.highest_index_yes_vote = previous);
+				}
+			},
+			Some(Vote::No) => Proposals::<T>::mutate(dao_id, proposal_id, |p| {
+				p.no_votes = p.no_votes.saturating_sub(weight);
+				p.no_shares = p.no_shares.saturating_sub(shares);
+			}),
+			_ => AbstainVotes::mutate(dao_id, proposal_id, |abstains| *abstains = abstains.saturating_sub(shares)),
+		}
+		Ok(())
+	}
+
+	/// What `highest_index_yes_vote` of a member goes back to when its yes vote at `proposal_index` is taken back.
+	/// That is the previous proposal it voted yes on, or the last processed one as it does not hold back a ragequit.
+	/// Only the unprocessed proposals are scanned, one read each, which the vote weights charge per queued proposal
+	fn previous_yes_vote(dao_id: DaoId, delegate: &T::AccountId, proposal_index: u128) -> u128 {
+		let proposal_queue = ProposalQueue::get(dao_id);
+		// the proposals before the next one to process are all processed
+		let next_to_process = NextToProcess::get(dao_id);
+		let mut index = proposal_index;
+		while index > next_to_process {
+			index -= 1;
+			if ProposalVotes::<T>::get((dao_id, proposal_queue[index as usize]), delegate.clone()) == 1 {
+				return index;
+			}
+		}
+		next_to_process.saturating_sub(1)
+	}
+
+	/// Process a standard proposal, the processing reward is credited to `processor`
+	pub fn do_process_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::Standard)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+
+		// TODO: guild is full
+
+		// Proposal passed
+		if did_pass {
+			// pay the applicant out of the guild bank first, nothing is written if it fails
+			if let Some(schedule) = ProposalVestings::take(dao_id, proposal_id) {
+				// the payment stays in the guild bank until it is claimed
+				let start = Self::get_current_period(dao_id).saturating_add(schedule.cliff);
+				let stream = PayoutStream {
+					recipient: proposal.applicant.clone(),
+					token: proposal.payment_token,
+					start,
+					periods: schedule.periods,
+					per_period: schedule.per_period,
+					claimed: 0,
+				};
+				StreamCommitments::<T>::mutate(dao_id, stream.token, |committed| *committed = committed.saturating_add(stream.total()));
+				PayoutStreams::<T>::insert(dao_id, proposal_id, stream);
+				Self::deposit_event(RawEvent::PayoutStreamStarted(
+					dao_id,
+					proposal_id,
+					proposal.applicant.clone(),
+					proposal.payment_token,
+					start,
+					schedule.periods,
+					schedule.per_period
+				));
+			} else if proposal.payment_requested > 0 {
+				Self::internal_transfer(dao_id, &Self::account_id(dao_id), &proposal.applicant, proposal.payment_token, proposal.payment_requested)?;
+				Self::deposit_event(RawEvent::PaymentDisbursed(
+					dao_id,
+					proposal_index,
+					proposal.applicant.clone(),
+					proposal.payment_token,
+					proposal.payment_requested
+				));
+			}
+
+
+			// if the applicant is already a member, add to their existing shares
+			if Members::<T>::contains_key(dao_id, &proposal.applicant) {
+				Members::<T>::mutate(dao_id, &proposal.applicant, |mem| {
+					mem.shares = mem.shares.checked_add(proposal.shares_requested).unwrap();
+					mem.loot = mem.loot.checked_add(proposal.loot_requested).unwrap();
+				});
+			} else {
+				// if the applicant address is already taken by a member's delegateKey, reset it to their member address
+				if AddressOfDelegates::<T>::contains_key(dao_id, proposal.applicant.clone()) {
+					let delegate = AddressOfDelegates::<T>::get(dao_id, proposal.applicant.clone());
+					Members::<T>::mutate(dao_id, delegate.clone(), |mem| {
+						mem.delegate_key = delegate.clone();
+					});
+					AddressOfDelegates::<T>::insert(dao_id, delegate.clone(), delegate.clone());
+				}
+				// add new member
+				let member = Member {
+					shares: proposal.shares_requested,
+					highest_index_yes_vote: 0,
+					loot: proposal.loot_requested,
+					jailed_at: 0,
+					exists: true,
+					delegate_key: proposal.applicant.clone(),
+				};
+				Members::<T>::insert(dao_id, proposal.applicant.clone(), member);
+				AddressOfDelegates::<T>::insert(dao_id, proposal.applicant.clone(), proposal.applicant.clone());
+			}
+
+			// mint new shares
+			let totoal_shares = TotalShares::get(dao_id).checked_add(proposal.shares_requested).unwrap();
+			TotalShares::insert(dao_id, totoal_shares);
+			Self::checkpoint_shares(dao_id, &proposal.applicant);
+			// transfer correponding balance from escrow to guild bank
+			Self::release_tribute(dao_id, proposal_id, true)?;
+		} else {
+			// Proposal failed
+			// return the tribute to whoever paid it
+			Self::release_tribute(dao_id, proposal_id, false)?;
+			ProposalVestings::remove(dao_id, proposal_id);
+		}
+
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		// need to mutate for update
+		Proposals::<T>::insert(dao_id, proposal_id, proposal.clone());
+		NextToProcess::insert(dao_id, proposal_index + 1);
+
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+
+		Self::deposit_event(RawEvent::ProcessProposal(
+			dao_id,
+			proposal_index, 
+			proposal.applicant.clone(),
+			proposal.proposer.clone(),
+			proposal.tribute_offered,
+			proposal.shares_requested,
+			did_pass
+		));
+		Ok(())
+	}
+
+	/// Process a guild kick proposal, the processing reward is credited to `processor`
+	pub fn do_process_guild_kick_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::GuildKick)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+		if did_pass {
+			// update memeber status, i.e. jailed and slash shares
+			Members::<T>::mutate(dao_id, proposal.applicant.clone(), |member| {
+				member.jailed_at = proposal_index;
+				member.loot = member.loot.checked_add(member.shares).unwrap();
+				let total_shares = TotalShares::get(dao_id).checked_sub(member.shares).unwrap();
+				let total_loot = TotalLoot::get(dao_id).checked_add(member.shares).unwrap();
+				TotalLoot::insert(dao_id, total_loot);
+				TotalShares::insert(dao_id, total_shares);
+				member.shares = 0;
+			});
+			// the shares are loot now, which the conviction locks do not hold back from `rage_kick`
+			ShareLocks::<T>::remove(dao_id, proposal.applicant.clone());
+			Self::checkpoint_shares(dao_id, &proposal.applicant);
+		}
+
+		ProsedToKick::<T>::insert(dao_id, proposal.applicant.clone(), false);
+
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		// need to mutate for update
+		Proposals::<T>::insert(dao_id, proposal_id, proposal.clone());
+		NextToProcess::insert(dao_id, proposal_index + 1);
+
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+		Ok(())
+	}
+
+	/// Process a whitelist proposal, the processing reward is credited to `processor`
+	pub fn do_process_whitelist_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::Whitelist)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+		if did_pass {
+			TokenWhitelist::<T>::insert(dao_id, proposal.tribute_token, true);
+			ApprovedTokens::<T>::append(dao_id, proposal.tribute_token);
+		}
+
+		ProposedToWhitelist::<T>::insert(dao_id, proposal.tribute_token, false);
+
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		// need to mutate for update
+		Proposals::<T>::insert(dao_id, proposal_id, proposal.clone());
+		NextToProcess::insert(dao_id, proposal_index + 1);
+
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+
+		Self::deposit_event(RawEvent::ProcessWhitelistProposal(dao_id, proposal_index, proposal.tribute_token, did_pass));
+		Ok(())
+	}
+
+	/// Process a cancel stream proposal, the stream stops vesting if it passes
+	pub fn do_process_cancel_stream_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::CancelStream)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+		let stream_id = ProposalStreamCancels::take(dao_id, proposal_id).unwrap_or_default();
+		if did_pass {
+			let current_period = Self::get_current_period(dao_id);
+			// the stream may be fully claimed already
+			if let Some(mut stream) = PayoutStreams::<T>::get(dao_id, stream_id) {
+				// what won't vest anymore is the guild bank's again
+				let unclaimed = stream.unclaimed();
+				stream.periods = stream.vested_periods(current_period);
+				let released = unclaimed.saturating_sub(stream.unclaimed());
+				StreamCommitments::<T>::mutate(dao_id, stream.token, |committed| *committed = committed.saturating_sub(released));
+				if stream.claimed >= stream.vested(current_period) {
+					PayoutStreams::<T>::remove(dao_id, stream_id);
+				} else {
+					PayoutStreams::<T>::insert(dao_id, stream_id, stream);
+				}
+			}
+		}
+
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		Proposals::<T>::insert(dao_id, proposal_id, proposal);
+		NextToProcess::insert(dao_id, proposal_index + 1);
+
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+
+		Self::deposit_event(RawEvent::ProcessCancelStreamProposal(dao_id, proposal_index, stream_id, did_pass));
+		Ok(())
+	}
+
+	/// Process a parameter change proposal, the settings are changed if it passes
+	pub fn do_process_parameter_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::ParameterChange)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+		let change = ProposalParameterChanges::<T>::take(dao_id, proposal_id).unwrap_or_default();
+		if did_pass {
+			if let Some(period_duration) = change.period_duration {
+				// the current period keeps its number and lasts the new duration from now on
+				PeriodAnchor::insert(dao_id, (T::Clock::now(), Self::get_current_period(dao_id)));
+				PeriodDuration::insert(dao_id, period_duration);
+			}
+			if let Some(voting_period_length) = change.voting_period_length {
+				VotingPeriodLength::insert(dao_id, voting_period_length);
+			}
+			if let Some(grace_period_length) = change.grace_period_length {
+				GracePeriodLength::insert(dao_id, grace_period_length);
+			}
+			if let Some(dilution_bound) = change.dilution_bound {
+				DilutionBound::insert(dao_id, dilution_bound);
+			}
+			// deposits and rewards of queued proposals are kept in their escrows
+			if let Some(proposal_deposit) = change.proposal_deposit {
+				ProposalDeposit::<T>::insert(dao_id, proposal_deposit);
+			}
+			if let Some(processing_reward) = change.processing_reward {
+				ProcessingReward::<T>::insert(dao_id, processing_reward);
+			}
+		}
+
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		Proposals::<T>::insert(dao_id, proposal_id, proposal);
+		NextToProcess::insert(dao_id, proposal_index + 1);
+
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+
+		Self::deposit_event(RawEvent::ProcessParameterProposal(dao_id, proposal_index, did_pass));
+		Ok(())
+	}
+
+	/// Process an action proposal, its call is dispatched from the action account if it passes.
+	/// A failing call does not fail the processing, its result is in `ActionExecuted` and its payment stays in the guild bank
+	pub fn do_process_action_proposal(dao_id: DaoId, proposal_index: u128, processor: &T::AccountId) -> dispatch::DispatchResult {
+		let (proposal_id, mut proposal) = Self::proposal_to_process(dao_id, proposal_index, ProposalKind::Action)?;
+		let did_pass = Self::should_pass(dao_id, proposal_index, proposal_id, &proposal);
+		let (payment_token, payment_requested) = (proposal.payment_token, proposal.payment_requested);
+
+		// processed before the call is dispatched, so that the call can not process it again
+		proposal.status = ProposalStatus::Processed { passed: did_pass };
+		Proposals::<T>::insert(dao_id, proposal_id, proposal);
+		NextToProcess::insert(dao_id, proposal_index + 1);
+		Self::pay_processing_reward(dao_id, proposal_id, processor)?;
+
+		let call = ProposalActions::<T>::take(dao_id, proposal_id);
+		Self::deposit_event(RawEvent::ProcessActionProposal(dao_id, proposal_index, did_pass));
+		if let (true, Some(call)) = (did_pass, call) {
+			let origin = frame_system::RawOrigin::Signed(Self::action_account(dao_id)).into();
+			// the call is not dispatched if it can't be funded, the funding is reverted if the call fails
+			let result = with_transaction(|| {
+				let result = Self::fund_action(dao_id, payment_token, payment_requested)
+					.and_then(|_| call.dispatch(origin).map(|_| ()).map_err(|e| e.error));
+				match result {
+					Ok(()) => TransactionOutcome::Commit(result),
+					Err(_) => TransactionOutcome::Rollback(result),
+				}
+			});
+			Self::deposit_event(RawEvent::ActionExecuted(dao_id, proposal_index, result));
+		}
+		Ok(())
+	}
+
+	/// Move the payment of a passed action proposal from the guild bank to the action account,
+	/// the caller reverts it if it fails
+	fn fund_action(dao_id: DaoId, token: T::CurrencyId, amount: u128) -> dispatch::DispatchResult {
+		if amount == 0 {
+			return Ok(());
+		}
+		let action_account = Self::action_account(dao_id);
+		Self::internal_transfer(dao_id, &Self::account_id(dao_id), &action_account, token, amount)?;
+		Self::withdraw(dao_id, &action_account, token, amount)
+	}
+
+	/// Process ready proposals within `budget`, visiting the DAOs in turn from `AutoProcessCursor`.
+	/// A DAO gets at most one proposal processed per block, so that a busy DAO can't starve the others.
+	/// Returns the weight used
+	pub fn auto_process(budget: Weight) -> Weight {
+		if budget.is_zero() {
+			return 0;
+		}
+		let db = T::DbWeight::get();
+		// processing moves funds, it waits for the pause to end
+		if Self::is_paused(CallCategory::Transfers) {
+			return db.reads(2);
+		}
+		// DaoCount, AutoProcessCursor and writing the cursor back
+		let mut used = db.reads_writes(2, 1);
+		let dao_count = DaoCount::get();
+		let mut dao_id = AutoProcessCursor::get();
+		for _ in 0..dao_count {
+			if dao_id >= dao_count {
+				dao_id = 0;
+			}
+			// finding the next ready proposal is what every `process_*` extrinsic does first, so the
+			// cheapest of them bounds it, on top of reading the queue length
+			let queue_len = Self::proposal_queue_len(dao_id);
+			let checks = Self::lookup_weight(queue_len);
+			let lookup = checks.saturating_add(db.reads(1));
+			if used.saturating_add(lookup) > budget {
+				break;
+			}
+			used = used.saturating_add(lookup);
+			if let Some((proposal_index, kind)) = Self::next_ready_proposal(dao_id) {
+				// the checks are part of the processing weight and already charged
+				let weight = Self::process_weight(kind, queue_len).saturating_sub(checks);
+				// a proposal too heavy for what is left is retried in the next block, the other DAOs go on
+				if used.saturating_add(weight) <= budget {
+					used = used.saturating_add(weight);
+					let destination = T::AutoProcessReward::get().unwrap_or_else(|| Self::account_id(dao_id));
+					if Self::auto_process_proposal(dao_id, proposal_index, kind, &destination).is_ok() {
+						Self::deposit_event(RawEvent::ProposalAutoProcessed(dao_id, proposal_index, destination));
+					}
+				}
+			}
+			dao_id += 1;
+		}
+		AutoProcessCursor::put(dao_id);
+		used
 	}
 
-	pub fn should_pass(proposal: ProposalOf<T>) -> bool {
-		let mut pass = proposal.yes_votes > proposal.no_votes;
+	/// Weight of the `process_*` extrinsic of `kind` with `queue_len` proposals in the queue
+	pub fn process_weight(kind: ProposalKind, queue_len: u32) -> Weight {
+		match kind {
+			ProposalKind::Standard => T::WeightInfo::process_proposal(queue_len),
+			ProposalKind::GuildKick => T::WeightInfo::process_guild_kick_proposal(queue_len),
+			ProposalKind::Whitelist => T::WeightInfo::process_whitelist_proposal(queue_len),
+			ProposalKind::Action => T::WeightInfo::process_action_proposal(queue_len).saturating_add(T::MaxActionWeight::get()),
+			ProposalKind::CancelStream => T::WeightInfo::process_cancel_stream_proposal(queue_len),
+			ProposalKind::ParameterChange => T::WeightInfo::process_parameter_proposal(queue_len),
+		}
+	}
+
+	/// Upper bound of `next_ready_proposal`, the lightest `process_*` extrinsic which starts with the same checks
+	fn lookup_weight(queue_len: u32) -> Weight {
+		[
+			ProposalKind::Standard,
+			ProposalKind::GuildKick,
+			ProposalKind::Whitelist,
+			ProposalKind::Action,
+			ProposalKind::CancelStream,
+			ProposalKind::ParameterChange,
+		].iter().map(|kind| Self::process_weight(*kind, queue_len)).min().unwrap_or_default()
+	}
+
+	/// The index and kind of the oldest unprocessed proposal of the queue if it can be processed now
+	pub fn next_ready_proposal(dao_id: DaoId) -> Option<(u128, ProposalKind)> {
+		let proposal_index = NextToProcess::get(dao_id);
+		let proposal_id = *ProposalQueue::get(dao_id).get(proposal_index as usize)?;
+		let kind = Proposals::<T>::get(dao_id, proposal_id).kind;
+		Self::proposal_to_process(dao_id, proposal_index, kind).ok().map(|_| (proposal_index, kind))
+	}
+
+	/// Process a proposal in `on_initialize`, nothing is written if it fails as there is no extrinsic to revert
+	#[transactional]
+	fn auto_process_proposal(dao_id: DaoId, proposal_index: u128, kind: ProposalKind, destination: &T::AccountId) -> dispatch::DispatchResult {
+		match kind {
+			ProposalKind::Standard => Self::do_process_proposal(dao_id, proposal_index, destination),
+			ProposalKind::GuildKick => Self::do_process_guild_kick_proposal(dao_id, proposal_index, destination),
+			ProposalKind::Whitelist => Self::do_process_whitelist_proposal(dao_id, proposal_index, destination),
+			ProposalKind::Action => Self::do_process_action_proposal(dao_id, proposal_index, destination),
+			ProposalKind::CancelStream => Self::do_process_cancel_stream_proposal(dao_id, proposal_index, destination),
+			ProposalKind::ParameterChange => Self::do_process_parameter_proposal(dao_id, proposal_index, destination),
+		}
+	}
+
+	/// The proposal at `proposal_index` of the queue if it can be processed now by the `process_*` extrinsic of `kind`
+	pub fn proposal_to_process(dao_id: DaoId, proposal_index: u128, kind: ProposalKind) -> Result<(u128, ProposalOf<T>), DispatchError> {
+		let proposal_queue = ProposalQueue::get(dao_id);
+		ensure!(proposal_index < proposal_queue.len() as u128, Error::<T>::ProposalNotExist);
+		let index = proposal_index as usize;
+		let proposal_id = proposal_queue[index];
+		let proposal = Proposals::<T>::get(dao_id, proposal_id);
+		let kind_error = match kind {
+			ProposalKind::Standard => Error::<T>::NotStandardProposal,
+			ProposalKind::Whitelist => Error::<T>::NotWhitelistProposal,
+			ProposalKind::GuildKick => Error::<T>::NotKickProposal,
+			ProposalKind::Action => Error::<T>::NotActionProposal,
+			ProposalKind::CancelStream => Error::<T>::NotCancelStreamProposal,
+			ProposalKind::ParameterChange => Error::<T>::NotParameterProposal,
+		};
+		ensure!(proposal.kind == kind, kind_error);
+		ensure!(Self::get_current_period(dao_id) >= proposal.grace_ends(), Error::<T>::ProposalNotReady);
+		ensure!(!proposal.is_processed(), Error::<T>::ProposalHasProcessed);
+		ensure!(index == 0 || Proposals::<T>::get(dao_id, proposal_queue[index - 1]).is_processed(), Error::<T>::PreviousProposalNotProcessed);
+		if kind == ProposalKind::Standard {
+			// shares+loot overflow
+			let total_requested = proposal.loot_requested.checked_add(proposal.shares_requested).ok_or(Error::<T>::SharesOverFlow)?;
+			let future_shares = TotalShares::get(dao_id).checked_add(total_requested).ok_or(Error::<T>::SharesOverFlow)?;
+			ensure!(future_shares.checked_add(TotalLoot::get(dao_id)).ok_or(Error::<T>::SharesOverFlow)? <= T::MaxShares::get(), Error::<T>::SharesOverFlow);
+		}
+		Ok((proposal_id, proposal))
+	}
+
+	/// Whether a proposal passes if it is processed now, without side effects
+	pub fn proposal_outcome(dao_id: DaoId, proposal_id: u128, proposal: &ProposalOf<T>) -> Result<(), FailureReason> {
 		// as anyone can process the proposal and get rewarded, so do not fail here
-		if TotalShares::get().checked_mul(DilutionBound::get()).unwrap() < proposal.max_total_shares_at_yes {
-			Self::deposit_event(RawEvent::DilutionBoundExeceeds(TotalShares::get(), DilutionBound::get(), proposal.max_total_shares_at_yes));
-			pass = false;
+		ensure!(
+			TotalShares::get(dao_id).saturating_mul(DilutionBound::get(dao_id)) >= proposal.max_total_shares_at_yes,
+			FailureReason::DilutionBoundExceeded
+		);
+		// the applicant of a cancel stream proposal is the recipient of the stream, which may well be jailed
+		if proposal.kind != ProposalKind::CancelStream {
+			ensure!(Members::<T>::get(dao_id, proposal.applicant.clone()).jailed_at == 0, FailureReason::ApplicantJailed);
+		}
+		let threshold = VotingThresholds::get(dao_id, proposal.kind);
+		// the votes are weighted by the shares held when the voting started
+		let total_shares = Self::total_shares_at(dao_id, proposal.starting_period);
+		// abstaining shares count toward the quorum but not the approval
+		let voted = proposal.yes_shares.saturating_add(proposal.no_shares).saturating_add(AbstainVotes::get(dao_id, proposal_id));
+		ensure!(voted >= threshold.quorum * total_shares, FailureReason::QuorumNotReached);
+		// the weighted votes decide, the thresholds are in shares
+		ensure!(proposal.yes_votes > proposal.no_votes, FailureReason::NotEnoughYesVotes);
+		ensure!(proposal.yes_shares >= threshold.approval * total_shares, FailureReason::ApprovalNotReached);
+		match proposal.kind {
+			ProposalKind::Standard | ProposalKind::Action => ensure!(
+				proposal.payment_requested <= Self::free_guild_bank_balance(dao_id, proposal.payment_token),
+				FailureReason::PaymentExceedsGuildBank
+			),
+			ProposalKind::Whitelist => ensure!(
+				ApprovedTokens::<T>::get(dao_id).len() < T::MaxTokens::get() as usize,
+				FailureReason::WhitelistFull
+			),
+			// another change may have passed since it was submitted
+			ProposalKind::ParameterChange => ensure!(
+				ProposalParameterChanges::<T>::get(dao_id, proposal_id).map_or(false, |change| Self::validate_parameter_change(dao_id, &change).is_ok()),
+				FailureReason::InvalidParameters
+			),
+			ProposalKind::GuildKick | ProposalKind::CancelStream => {},
 		}
+		Ok(())
+	}
 
-		if Members::<T>::get(proposal.applicant.clone()).jailed_at != 0 {
-			pass = false;
+	/// `proposal_outcome` of a proposal being processed, emits `ProposalFailed` with the reason if it fails
+	pub fn should_pass(dao_id: DaoId, proposal_index: u128, proposal_id: u128, proposal: &ProposalOf<T>) -> bool {
+		let outcome = Self::proposal_outcome(dao_id, proposal_id, proposal);
+		if let Err(reason) = outcome {
+			if reason == FailureReason::DilutionBoundExceeded {
+				Self::deposit_event(RawEvent::DilutionBoundExeceeds(dao_id, TotalShares::get(dao_id), DilutionBound::get(dao_id), proposal.max_total_shares_at_yes));
+			}
+			Self::deposit_event(RawEvent::ProposalFailed(dao_id, proposal_index, reason));
 		}
-		pass
+		outcome.is_ok()
+	}
+
+	/// Why a proposal would fail with its current votes, `None` if it would pass, whether it is ready or not
+	pub fn preview_outcome(dao_id: DaoId, proposal_id: u128) -> Result<Option<FailureReason>, DispatchError> {
+		ensure!(Proposals::<T>::contains_key(dao_id, proposal_id), Error::<T>::ProposalNotExist);
+		Ok(Self::proposal_outcome(dao_id, proposal_id, &Proposals::<T>::get(dao_id, proposal_id)).err())
+	}
+
+	/// What processing the proposal at `proposal_index` of the queue would do, fails as the `process_*` extrinsic would
+	pub fn preview_process(dao_id: DaoId, proposal_index: u128) -> Result<ProcessPreview<T::CurrencyId>, DispatchError> {
+		let proposal_id = *ProposalQueue::get(dao_id).get(proposal_index as usize).ok_or(Error::<T>::ProposalNotExist)?;
+		let kind = Proposals::<T>::get(dao_id, proposal_id).kind;
+		let (proposal_id, proposal) = Self::proposal_to_process(dao_id, proposal_index, kind)?;
+		let failure = Self::proposal_outcome(dao_id, proposal_id, &proposal).err();
+		let (reward_token, reward) = Self::processing_reward_of(dao_id, proposal_id);
+		Ok(ProcessPreview {
+			passed: failure.is_none(),
+			failure: failure,
+			reward_token: reward_token,
+			reward: reward,
+		})
 	}
 
-	pub fn member_quit(who: T::AccountId, shares_to_burn: u128, loot_to_burn: u128) -> dispatch::DispatchResult {
-		ensure!(Members::<T>::contains_key(who.clone()), Error::<T>::NotMember);
-		let member = Members::<T>::get(who.clone());
+	/// The tokens credited to `who` for burning the shares and loot, fails as `member_quit` would
+	pub fn ragequit_payout(dao_id: DaoId, who: &T::AccountId, shares_to_burn: u128, loot_to_burn: u128) -> Result<Vec<(T::CurrencyId, u128)>, DispatchError> {
+		ensure!(Members::<T>::contains_key(dao_id, who.clone()), Error::<T>::NotMember);
+		let member = Members::<T>::get(dao_id, who.clone());
 		ensure!(member.shares >= shares_to_burn, Error::<T>::NoEnoughShares);
+		ensure!(member.shares - shares_to_burn >= Self::locked_shares(dao_id, who), Error::<T>::SharesLocked);
+		ensure!(member.loot >= loot_to_burn, Error::<T>::NoEnoughLoot);
 		// check if can rage quit
 		let proposal_index = member.highest_index_yes_vote;
-		ensure!(proposal_index < ProposalQueue::get().len().try_into().unwrap(), Error::<T>::ProposalNotExist);
-		let _usize_proposal_index = TryInto::<usize>::try_into(proposal_index).ok().unwrap();
-		let proposal_id = ProposalQueue::get()[_usize_proposal_index];
-		let proposal =  Proposals::<T>::get(proposal_id);
-		ensure!(proposal.flags[1], Error::<T>::ProposalNotProcessed);
-			
-		// burn shares and loot
-		Members::<T>::mutate(who.clone(), |mem| {
-			mem.shares = member.shares.checked_sub(shares_to_burn).unwrap();
-			mem.loot = member.loot.checked_sub(loot_to_burn).unwrap();
+		let proposal_queue = ProposalQueue::get(dao_id);
+		ensure!(proposal_index < proposal_queue.len() as u128, Error::<T>::ProposalNotExist);
+		let proposal = Proposals::<T>::get(dao_id, proposal_queue[proposal_index as usize]);
+		ensure!(proposal.is_processed(), Error::<T>::ProposalNotProcessed);
+
+		// the fair share of every whitelisted token, the payout streams are paid first
+		let initial_total = TotalShares::get(dao_id).checked_add(TotalLoot::get(dao_id)).ok_or(Error::<T>::StorageOverflow)?;
+		let total_to_burn = shares_to_burn.checked_add(loot_to_burn).ok_or(Error::<T>::StorageOverflow)?;
+		ApprovedTokens::<T>::get(dao_id).into_iter().map(|token| {
+			let amount = Self::free_guild_bank_balance(dao_id, token);
+			let balance = amount.checked_mul(total_to_burn).ok_or(Error::<T>::StorageOverflow)?.checked_div(initial_total).unwrap_or_default();
+			Ok((token, balance))
+		}).collect()
+	}
+
+	/// Shares of `who` which can not be ragequit in the current period, the locks overlap rather than add up
+	pub fn locked_shares(dao_id: DaoId, who: &T::AccountId) -> u128 {
+		let current_period = Self::get_current_period(dao_id);
+		ShareLocks::<T>::get(dao_id, who.clone()).iter()
+			.filter(|lock| lock.until > current_period)
+			.map(|lock| lock.shares)
+			.max()
+			.unwrap_or_default()
+	}
 
+	pub fn member_quit(dao_id: DaoId, who: T::AccountId, shares_to_burn: u128, loot_to_burn: u128) -> dispatch::DispatchResult {
+		let payout = Self::ragequit_payout(dao_id, &who, shares_to_burn, loot_to_burn)?;
+
+		// burn shares and loot
+		Members::<T>::mutate(dao_id, who.clone(), |mem| {
+			mem.shares -= shares_to_burn;
+			mem.loot -= loot_to_burn;
 		});
-		let initial_total = TotalShares::get().checked_add(TotalLoot::get()).unwrap();
-		let total_to_burn = shares_to_burn.checked_add(loot_to_burn).unwrap();
-		let rest_shares = TotalShares::get().checked_sub(shares_to_burn).unwrap();
-		TotalShares::put(rest_shares);
-		let rest_loot = TotalLoot::get().checked_sub(loot_to_burn).unwrap();
-		TotalLoot::put(rest_loot);
-
-		// withdraw the tokens
-		let amount = Self::balance_to_u128(T::Currency::free_balance(&Self::account_id()));
-		let balance = amount.checked_mul(total_to_burn).unwrap().checked_div(initial_total).unwrap();
-		let _ = T::Currency::transfer(&Self::account_id(), &who, Self::u128_to_balance(balance), KeepAlive);			
-
-		Self::deposit_event(RawEvent::Ragequit(who.clone(), shares_to_burn));
+		let rest_shares = TotalShares::get(dao_id).checked_sub(shares_to_burn).unwrap();
+		TotalShares::insert(dao_id, rest_shares);
+		Self::checkpoint_shares(dao_id, &who);
+		let rest_loot = TotalLoot::get(dao_id).checked_sub(loot_to_burn).unwrap();
+		TotalLoot::insert(dao_id, rest_loot);
+
+		// credit the fair share of every whitelisted token, members withdraw it by themselves
+		for (token, balance) in payout.into_iter() {
+			Self::internal_transfer(dao_id, &Self::account_id(dao_id), &who, token, balance)?;
+		}
+
+		Self::deposit_event(RawEvent::Ragequit(dao_id, who.clone(), shares_to_burn));
 		Ok(())
 	}
-}
\ No newline at end of file
+
+	/// Checkpoint the shares of `who` and the total shares of the DAO, called after every change of them.
+	/// Checkpoints which no unprocessed proposal can be weighted by any more are pruned on the way
+	fn checkpoint_shares(dao_id: DaoId, who: &T::AccountId) {
+		let period = Self::get_current_period(dao_id);
+		let from = Self::oldest_voting_period(dao_id, period);
+		let shares = Members::<T>::get(dao_id, who).shares;
+		ShareCheckpoints::<T>::mutate(dao_id, who, |checkpoints| {
+			Self::push_checkpoint(checkpoints, period, shares);
+			Self::prune_checkpoints(checkpoints, from);
+		});
+		let total_shares = TotalShares::get(dao_id);
+		TotalShareCheckpoints::mutate(dao_id, |checkpoints| {
+			Self::push_checkpoint(checkpoints, period, total_shares);
+			Self::prune_checkpoints(checkpoints, from);
+		});
+	}
+
+	/// The starting period of the first unprocessed proposal in the queue, proposals sponsored later start
+	/// after `current_period`. No vote or outcome is weighted by the shares of an earlier period
+	fn oldest_voting_period(dao_id: DaoId, current_period: u128) -> u128 {
+		ProposalQueue::get(dao_id).get(NextToProcess::get(dao_id) as usize)
+			.map(|proposal_id| Proposals::<T>::get(dao_id, proposal_id).starting_period)
+			.unwrap_or_else(|| current_period.saturating_add(1))
+	}
+
+	/// Drop the checkpoints before `from`, except the last one which is the value when `from` starts
+	fn prune_checkpoints(checkpoints: &mut Vec<(u128, u128)>, from: u128) {
+		let first_kept = checkpoints.iter().position(|(at, _)| *at >= from).unwrap_or(checkpoints.len());
+		if first_kept > 1 {
+			checkpoints.drain(..first_kept - 1);
+		}
+	}
+
+	fn push_checkpoint(checkpoints: &mut Vec<(u128, u128)>, period: u128, value: u128) {
+		match checkpoints.last_mut() {
+			Some(last) if last.0 == period => last.1 = value,
+			_ => checkpoints.push((period, value)),
+		}
+	}
+
+	/// The value when `period` starts, i.e. of the last checkpoint in an earlier period
+	fn value_at(checkpoints: &[(u128, u128)], period: u128) -> u128 {
+		match checkpoints.binary_search_by(|(at, _)| at.cmp(&period)) {
+			Ok(0) | Err(0) => 0,
+			Ok(index) | Err(index) => checkpoints[index - 1].1,
+		}
+	}
+
+	/// Shares of `who` when `period` starts, the voting power on proposals starting in that period
+	pub fn shares_at(dao_id: DaoId, who: &T::AccountId, period: u128) -> u128 {
+		Self::value_at(&ShareCheckpoints::<T>::get(dao_id, who), period)
+	}
+
+	/// Total shares of the DAO when `period` starts
+	pub fn total_shares_at(dao_id: DaoId, period: u128) -> u128 {
+		Self::value_at(&TotalShareCheckpoints::get(dao_id), period)
+	}
+}
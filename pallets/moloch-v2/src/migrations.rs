@@ -0,0 +1,445 @@
+//! Storage migrations of the pallet, each one runs once and bumps `StorageVersion`
+use super::*;
+use frame_support::{StorageValue, StorageMap, StorageDoubleMap, IterableStorageMap, IterableStorageDoubleMap};
+use frame_support::storage::migration::{StorageIterator, put_storage_value, take_storage_value};
+use sp_std::vec;
+
+/// Prefix of the pallet's storage, the single-DAO layout is read through it as its types are gone
+const MODULE: &[u8] = b"MolochV2";
+
+/// Proposal layout of the single DAO, before tokens, `ProposalKind` and `ProposalStatus`
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct ProposalV1<AccountId> {
+	pub proposer: AccountId,
+	pub applicant: AccountId,
+	pub sponsor: AccountId,
+	pub shares_requested: u128,
+	pub loot_requested: u128,
+	pub payment_requested: u128,
+	pub tribute_offered: u128,
+	// [sponsored, processed, didPass, cancelled, whitelist, guildkick]
+	pub flags: [bool; 6],
+	pub starting_period: u128,
+	pub yes_votes: u128,
+	pub no_votes: u128,
+	pub details: Vec<u8>,
+	pub max_total_shares_at_yes: u128,
+}
+
+impl<AccountId> ProposalV1<AccountId> {
+	/// The proposal with its flags replaced by its kind and status, it was paid in `token`
+	pub fn with_token<CurrencyId: Copy>(self, token: CurrencyId) -> ProposalV2<AccountId, CurrencyId> {
+		let kind = match self.flags {
+			[_, _, _, _, true, _] => ProposalKind::Whitelist,
+			[_, _, _, _, _, true] => ProposalKind::GuildKick,
+			_ => ProposalKind::Standard,
+		};
+		let status = match self.flags {
+			[_, true, passed, _, _, _] => ProposalStatus::Processed { passed },
+			[_, _, _, true, _, _] => ProposalStatus::Cancelled,
+			[true, _, _, _, _, _] => ProposalStatus::Sponsored,
+			_ => ProposalStatus::Submitted,
+		};
+		ProposalV2 {
+			proposer: self.proposer,
+			applicant: self.applicant,
+			sponsor: self.sponsor,
+			shares_requested: self.shares_requested,
+			loot_requested: self.loot_requested,
+			payment_requested: self.payment_requested,
+			payment_token: token,
+			tribute_offered: self.tribute_offered,
+			tribute_token: token,
+			kind,
+			status,
+			starting_period: self.starting_period,
+			yes_votes: self.yes_votes,
+			no_votes: self.no_votes,
+			details: self.details,
+			max_total_shares_at_yes: self.max_total_shares_at_yes,
+		}
+	}
+}
+
+/// Proposal layout from `ProposalKind` and `ProposalStatus` until the voting mode, votes are shares
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct ProposalV2<AccountId, CurrencyId> {
+	pub proposer: AccountId,
+	pub applicant: AccountId,
+	pub sponsor: AccountId,
+	pub shares_requested: u128,
+	pub loot_requested: u128,
+	pub payment_requested: u128,
+	pub payment_token: CurrencyId,
+	pub tribute_offered: u128,
+	pub tribute_token: CurrencyId,
+	pub kind: ProposalKind,
+	pub status: ProposalStatus,
+	pub starting_period: u128,
+	pub yes_votes: u128,
+	pub no_votes: u128,
+	pub details: Vec<u8>,
+	pub max_total_shares_at_yes: u128,
+}
+
+/// Proposal layout from the voting mode until the voting and grace period lengths
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct ProposalV6<AccountId, CurrencyId> {
+	pub proposer: AccountId,
+	pub applicant: AccountId,
+	pub sponsor: AccountId,
+	pub shares_requested: u128,
+	pub loot_requested: u128,
+	pub payment_requested: u128,
+	pub payment_token: CurrencyId,
+	pub tribute_offered: u128,
+	pub tribute_token: CurrencyId,
+	pub kind: ProposalKind,
+	pub status: ProposalStatus,
+	pub starting_period: u128,
+	pub voting_mode: VotingMode,
+	pub yes_votes: u128,
+	pub no_votes: u128,
+	pub yes_shares: u128,
+	pub no_shares: u128,
+	pub details: Vec<u8>,
+	pub max_total_shares_at_yes: u128,
+}
+
+impl<AccountId, CurrencyId> ProposalV6<AccountId, CurrencyId> {
+	/// The proposal with the lengths it votes and waits with
+	pub fn with_lengths(self, voting_period_length: u128, grace_period_length: u128) -> Proposal<AccountId, CurrencyId> {
+		Proposal {
+			proposer: self.proposer,
+			applicant: self.applicant,
+			sponsor: self.sponsor,
+			shares_requested: self.shares_requested,
+			loot_requested: self.loot_requested,
+			payment_requested: self.payment_requested,
+			payment_token: self.payment_token,
+			tribute_offered: self.tribute_offered,
+			tribute_token: self.tribute_token,
+			kind: self.kind,
+			status: self.status,
+			starting_period: self.starting_period,
+			voting_period_length,
+			grace_period_length,
+			voting_mode: self.voting_mode,
+			yes_votes: self.yes_votes,
+			no_votes: self.no_votes,
+			yes_shares: self.yes_shares,
+			no_shares: self.no_shares,
+			details: self.details,
+			max_total_shares_at_yes: self.max_total_shares_at_yes,
+		}
+	}
+}
+
+impl<AccountId, CurrencyId> From<ProposalV2<AccountId, CurrencyId>> for ProposalV6<AccountId, CurrencyId> {
+	fn from(old: ProposalV2<AccountId, CurrencyId>) -> Self {
+		ProposalV6 {
+			proposer: old.proposer,
+			applicant: old.applicant,
+			sponsor: old.sponsor,
+			shares_requested: old.shares_requested,
+			loot_requested: old.loot_requested,
+			payment_requested: old.payment_requested,
+			payment_token: old.payment_token,
+			tribute_offered: old.tribute_offered,
+			tribute_token: old.tribute_token,
+			kind: old.kind,
+			status: old.status,
+			starting_period: old.starting_period,
+			voting_mode: VotingMode::Linear,
+			yes_votes: old.yes_votes,
+			no_votes: old.no_votes,
+			// linear votes are the shares
+			yes_shares: old.yes_votes,
+			no_shares: old.no_votes,
+			details: old.details,
+			max_total_shares_at_yes: old.max_total_shares_at_yes,
+		}
+	}
+}
+
+/// Escrow layout until the processing reward was fixed at sponsoring time
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct EscrowV1<AccountId, CurrencyId> {
+	pub tribute_payer: AccountId,
+	pub tribute_token: CurrencyId,
+	pub tribute: u128,
+	pub deposit_payer: AccountId,
+	pub deposit_token: CurrencyId,
+	pub deposit: u128,
+}
+
+impl<AccountId, CurrencyId> EscrowV1<AccountId, CurrencyId> {
+	/// The escrow with the processing reward its proposal is processed for, at most the deposit
+	pub fn with_reward(self, processing_reward: u128) -> Escrow<AccountId, CurrencyId> {
+		Escrow {
+			tribute_payer: self.tribute_payer,
+			tribute_token: self.tribute_token,
+			tribute: self.tribute,
+			deposit_payer: self.deposit_payer,
+			deposit_token: self.deposit_token,
+			deposit: self.deposit,
+			processing_reward: processing_reward.min(self.deposit),
+		}
+	}
+}
+
+/// Skip the `blake2_128_concat` hash in front of a map key of the single-DAO layout and decode the key
+fn key_of<K: Decode>(hashed: &mut &[u8]) -> Option<K> {
+	*hashed = hashed.get(16..)?;
+	K::decode(hashed).ok()
+}
+
+/// Take a value of the single-DAO layout out of storage, `taken` counts the entries taken
+fn take_value<V: Decode>(item: &[u8], taken: &mut Weight) -> Option<V> {
+	*taken += 1;
+	take_storage_value(MODULE, item, &[])
+}
+
+/// Take the entries of a map of the single-DAO layout out of storage before any of DAO 0 is written under
+/// the same prefix, the keys are decoded by `key`
+fn take_map<K, V: Decode, F: Fn(&mut &[u8]) -> Option<K>>(item: &[u8], key: F, taken: &mut Weight) -> Vec<(K, V)> {
+	let entries: Vec<(K, V)> = StorageIterator::<V>::new(MODULE, item).drain()
+		.filter_map(|(hashed, value)| key(&mut &hashed[..]).map(|key| (key, value)))
+		.collect();
+	*taken += entries.len() as Weight;
+	entries
+}
+
+/// Move the single DAO into DAO 0 and replace the flags of its proposals with their kind and status.
+///
+/// Its funds were held in the native token by the pallet's account and the tributes of pending proposals
+/// by its custody account, both are moved to the account of DAO 0. The tributes and the deposits of
+/// pending proposals are escrowed, the rest less the existential deposit becomes the guild bank.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V1_0_0 {
+		return 0;
+	}
+
+	let mut taken: Weight = 0;
+	let summon_time = match take_value::<T::Moment>(b"SummonTime", &mut taken) {
+		Some(summon_time) => summon_time,
+		// nothing has been summoned
+		None => {
+			StorageVersion::put(Releases::V2_0_0);
+			return T::DbWeight::get().reads_writes(2, 1);
+		},
+	};
+	let dao_id: DaoId = 0;
+	let token = T::NativeCurrencyId::get();
+
+	// a `pallet_timestamp` moment until `migrate_to_v4`
+	put_storage_value(MODULE, b"SummonTime", &SummonTime::hashed_key_for(dao_id)[32..], summon_time);
+	PeriodDuration::insert(dao_id, take_value::<u32>(b"PeriodDuration", &mut taken).unwrap_or_default());
+	VotingPeriodLength::insert(dao_id, take_value::<u128>(b"VotingPeriodLength", &mut taken).unwrap_or_default());
+	GracePeriodLength::insert(dao_id, take_value::<u128>(b"GracePeriodLength", &mut taken).unwrap_or_default());
+	DilutionBound::insert(dao_id, take_value::<u128>(b"DilutionBound", &mut taken).unwrap_or_default());
+	let proposal_deposit = take_value::<BalanceOf<T>>(b"ProposalDeposit", &mut taken).unwrap_or_default();
+	ProposalDeposit::<T>::insert(dao_id, proposal_deposit);
+	ProcessingReward::<T>::insert(dao_id, take_value::<BalanceOf<T>>(b"ProcessingReward", &mut taken).unwrap_or_default());
+	TotalShares::insert(dao_id, take_value::<u128>(b"TotalShares", &mut taken).unwrap_or_default());
+	TotalLoot::insert(dao_id, take_value::<u128>(b"TotalLoot", &mut taken).unwrap_or_default());
+	ProposalCount::insert(dao_id, take_value::<u128>(b"ProposalCount", &mut taken).unwrap_or_default());
+	ProposalQueue::insert(dao_id, take_value::<Vec<u128>>(b"ProposalQueue", &mut taken).unwrap_or_default());
+	ApprovedTokens::<T>::insert(dao_id, vec![token]);
+	TokenWhitelist::<T>::insert(dao_id, token, true);
+	DaoCount::put(1);
+
+	let members = take_map::<T::AccountId, MemberOf<T>, _>(b"Members", key_of, &mut taken);
+	let delegates = take_map::<T::AccountId, T::AccountId, _>(b"AddressOfDelegates", key_of, &mut taken);
+	let kicks = take_map::<T::AccountId, bool, _>(b"ProsedToKick", key_of, &mut taken);
+	let votes = take_map::<(u128, T::AccountId), u8, _>(b"ProposalVotes", |hashed| Some((key_of(hashed)?, key_of(hashed)?)), &mut taken);
+	let proposals = take_map::<u128, ProposalV1<T::AccountId>, _>(b"Proposals", key_of, &mut taken);
+	for (who, member) in members {
+		Members::<T>::insert(dao_id, who, member);
+	}
+	for (delegate_key, who) in delegates {
+		AddressOfDelegates::<T>::insert(dao_id, delegate_key, who);
+	}
+	for (who, proposed) in kicks {
+		ProsedToKick::<T>::insert(dao_id, who, proposed);
+	}
+	for ((proposal_id, who), vote_unit) in votes {
+		ProposalVotes::<T>::insert((dao_id, proposal_id), who, vote_unit);
+	}
+
+	// the tributes of pending proposals are in custody, the deposits of sponsored ones in the guild bank
+	let mut escrows: Weight = 0;
+	let mut escrowed: u128 = 0;
+	for (proposal_id, old) in proposals {
+		let proposal = old.with_token(token);
+		let deposit = match proposal.status {
+			ProposalStatus::Submitted => Some(0),
+			ProposalStatus::Sponsored => Some(Module::<T>::balance_to_u128(proposal_deposit)),
+			_ => None,
+		};
+		if let Some(deposit) = deposit {
+			let escrow = EscrowV1 {
+				tribute_payer: proposal.proposer.clone(),
+				tribute_token: token,
+				tribute: proposal.tribute_offered,
+				deposit_payer: proposal.sponsor.clone(),
+				deposit_token: token,
+				deposit,
+			};
+			escrowed = escrowed.saturating_add(escrow.tribute).saturating_add(escrow.deposit);
+			// in the layout of `V8_0_0`, which `migrate_to_v9` reads
+			put_storage_value(MODULE, b"ProposalEscrows", &ProposalEscrows::<T>::hashed_key_for(dao_id, proposal_id)[32..], escrow);
+			escrows += 1;
+		}
+		// in the layout of `V2_0_0`, which `migrate_to_v6` reads
+		put_storage_value(MODULE, b"Proposals", &Proposals::<T>::hashed_key_for(dao_id, proposal_id)[32..], proposal);
+	}
+
+	let mut moved: u128 = 0;
+	let old_accounts: [T::AccountId; 2] = [T::ModuleId::get().into_account(), T::ModuleId::get().into_sub_account("custody")];
+	for old_account in old_accounts.iter() {
+		let free = T::Currency::free_balance(token, old_account);
+		if T::Currency::transfer(token, old_account, &Module::<T>::account_id(dao_id), free).is_ok() {
+			moved = moved.saturating_add(Module::<T>::balance_to_u128(free));
+		}
+	}
+	// the existential deposit keeps the account alive, like the one paid when summoning
+	let guild_bank = moved.saturating_sub(escrowed)
+		.saturating_sub(Module::<T>::balance_to_u128(T::Currency::minimum_balance(token)));
+	UserTokenBalances::<T>::insert((dao_id, Module::<T>::account_id(dao_id)), token, guild_bank);
+	UserTokenBalances::<T>::insert((dao_id, Module::<T>::custody_account(dao_id)), token, escrowed);
+	StorageVersion::put(Releases::V2_0_0);
+
+	// every entry taken is written again under DAO 0, the transfers touch three accounts
+	T::DbWeight::get().reads_writes(taken + 4, 2 * taken + escrows + 9)
+}
+
+/// Track the first unprocessed proposal of every queue for the auto-processor
+pub fn migrate_to_v3<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V2_0_0 {
+		return 0;
+	}
+
+	let mut read: Weight = 0;
+	let dao_count = DaoCount::get();
+	for dao_id in 0..dao_count {
+		let proposal_queue = ProposalQueue::get(dao_id);
+		let next = proposal_queue.iter()
+			.position(|proposal_id| {
+				read += 1;
+				!Proposals::<T>::get(dao_id, proposal_id).is_processed()
+			})
+			.unwrap_or(proposal_queue.len());
+		NextToProcess::insert(dao_id, next as u128);
+	}
+	StorageVersion::put(Releases::V3_0_0);
+
+	let dao_count = dao_count as Weight;
+	T::DbWeight::get().reads_writes(read + dao_count + 2, dao_count + 1)
+}
+
+/// Convert the summon time of every DAO from a `pallet_timestamp` moment to seconds, the unit of the
+/// `TimestampClock` which all of them were summoned with. A runtime switching to another clock has to
+/// rebase `SummonTime` and `PeriodDuration` itself
+pub fn migrate_to_v4<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V3_0_0 {
+		return 0;
+	}
+
+	let mut translated: Weight = 0;
+	SummonTime::translate::<T::Moment, _>(|_, moment| {
+		translated += 1;
+		// the timestamp is in milli seconds
+		Some(TryInto::<u128>::try_into(moment).unwrap_or_default() / 1000)
+	});
+	StorageVersion::put(Releases::V4_0_0);
+
+	T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+}
+
+/// Checkpoint the current shares of every member and DAO in period 0, so that proposals which are
+/// already voting keep being weighted by the shares from before the upgrade
+pub fn migrate_to_v5<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V4_0_0 {
+		return 0;
+	}
+
+	let mut members: Weight = 0;
+	for (dao_id, who, member) in Members::<T>::iter() {
+		ShareCheckpoints::<T>::insert(dao_id, who, vec![(0, member.shares)]);
+		members += 1;
+	}
+	let dao_count = DaoCount::get();
+	for dao_id in 0..dao_count {
+		TotalShareCheckpoints::insert(dao_id, vec![(0, TotalShares::get(dao_id))]);
+	}
+	StorageVersion::put(Releases::V5_0_0);
+
+	let dao_count = dao_count as Weight;
+	T::DbWeight::get().reads_writes(members + dao_count + 2, members + dao_count + 1)
+}
+
+/// Add the voting mode and the voted shares to every stored proposal, all of them were voted linearly
+pub fn migrate_to_v6<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V5_0_0 {
+		return 0;
+	}
+
+	// written in the layout of `V6_0_0`, which `migrate_to_v8` reads
+	let mut translated: Weight = 0;
+	for (key, old) in StorageIterator::<ProposalV2<T::AccountId, T::CurrencyId>>::new(MODULE, b"Proposals") {
+		put_storage_value(MODULE, b"Proposals", &key, ProposalV6::from(old));
+		translated += 1;
+	}
+	StorageVersion::put(Releases::V6_0_0);
+
+	T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+}
+
+/// Commit the unclaimed payments of the payout streams, so that `ragequit` and new payments leave them in the guild bank
+pub fn migrate_to_v7<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V6_0_0 {
+		return 0;
+	}
+
+	let mut streams: Weight = 0;
+	for (dao_id, _, stream) in PayoutStreams::<T>::iter() {
+		StreamCommitments::<T>::mutate(dao_id, stream.token, |committed| *committed = committed.saturating_add(stream.unclaimed()));
+		streams += 1;
+	}
+	StorageVersion::put(Releases::V7_0_0);
+
+	T::DbWeight::get().reads_writes(2 * streams + 1, streams + 1)
+}
+
+/// Store the voting and grace period lengths of the DAO on every proposal, which used the current ones until now
+pub fn migrate_to_v8<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V7_0_0 {
+		return 0;
+	}
+
+	let mut translated: Weight = 0;
+	Proposals::<T>::translate::<ProposalV6<T::AccountId, T::CurrencyId>, _>(|dao_id, _, old| {
+		translated += 1;
+		Some(old.with_lengths(VotingPeriodLength::get(dao_id), GracePeriodLength::get(dao_id)))
+	});
+	StorageVersion::put(Releases::V8_0_0);
+
+	T::DbWeight::get().reads_writes(3 * translated + 1, translated + 1)
+}
+
+/// Fix the current processing reward of the DAO on every escrow, which is what processing paid until now
+pub fn migrate_to_v9<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V8_0_0 {
+		return 0;
+	}
+
+	let mut translated: Weight = 0;
+	ProposalEscrows::<T>::translate::<EscrowV1<T::AccountId, T::CurrencyId>, _>(|dao_id, _, old| {
+		translated += 1;
+		Some(old.with_reward(Module::<T>::balance_to_u128(ProcessingReward::<T>::get(dao_id))))
+	});
+	StorageVersion::put(Releases::V9_0_0);
+
+	T::DbWeight::get().reads_writes(2 * translated + 1, translated + 1)
+}
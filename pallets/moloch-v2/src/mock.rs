@@ -1,7 +1,8 @@
 use crate::{Module, Config};
 use frame_system as system;
 use sp_core::H256;
-use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, impl_outer_event, impl_outer_dispatch, parameter_types, traits::{Get, Filter}, weights::Weight};
+use std::cell::RefCell;
 use sp_runtime::{
 	Perbill, ModuleId,
 	testing::Header,
@@ -13,13 +14,23 @@ impl_outer_origin! {
 }
 
 mod moloch_v2 {
-	pub use crate::Event;
+	pub use crate::{Event, Call};
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_balances::Balances,
+		moloch_v2::MolochV2,
+	}
 }
 
 impl_outer_event! {
 	pub enum Event for Test {
 		system<T>,
 		pallet_balances<T>,
+		orml_tokens<T>,
+		orml_currencies<T>,
 		moloch_v2<T>,
 	}
 }
@@ -44,12 +55,47 @@ parameter_types! {
     pub const MaxGracePeriodLength: u128 = 100_000_000; // maximum length of grace period
     pub const MaxDilutionBound: u128 = 100_000_000; // maximum dilution bound
     pub const MaxShares: u128 = 100_000_000; // maximum number of shares that can be minted
+    pub const MaxTokens: u32 = 3; // maximum number of whitelisted tokens
+    pub const MaxActionWeight: Weight = 1_000_000_000; // maximum weight of the call of an action proposal
+}
+
+// action proposals can not call back into the pallet
+pub struct ActionCallFilter;
+impl Filter<Call> for ActionCallFilter {
+	fn filter(call: &Call) -> bool {
+		!matches!(call, Call::MolochV2(_))
+	}
+}
+
+thread_local! {
+	static AUTO_PROCESS_BUDGET: RefCell<Weight> = RefCell::new(0);
+	static AUTO_PROCESS_REWARD: RefCell<Option<u64>> = RefCell::new(None);
+}
+
+// the auto-processor is disabled unless a test turns it on with `set_auto_process`
+pub struct AutoProcessBudget;
+impl Get<Weight> for AutoProcessBudget {
+	fn get() -> Weight {
+		AUTO_PROCESS_BUDGET.with(|v| *v.borrow())
+	}
+}
+
+pub struct AutoProcessReward;
+impl Get<Option<u64>> for AutoProcessReward {
+	fn get() -> Option<u64> {
+		AUTO_PROCESS_REWARD.with(|v| *v.borrow())
+	}
+}
+
+pub fn set_auto_process(budget: Weight, reward: Option<u64>) {
+	AUTO_PROCESS_BUDGET.with(|v| *v.borrow_mut() = budget);
+	AUTO_PROCESS_REWARD.with(|v| *v.borrow_mut() = reward);
 }
 
 impl system::Trait for Test {
 	type BaseCallFilter = ();
 	type Origin = Origin;
-	type Call = ();
+	type Call = Call;
 	type Index = u64;
 	type BlockNumber = u64;
 	type Hash = H256;
@@ -87,6 +133,34 @@ impl pallet_balances::Trait for Test {
 	type WeightInfo = ();
 }
 
+pub type CurrencyId = u32;
+pub type Amount = i64;
+pub const DORA: CurrencyId = 0;
+pub const USDT: CurrencyId = 1;
+pub const BTC: CurrencyId = 2;
+pub const ETH: CurrencyId = 3;
+
+impl orml_tokens::Trait for Test {
+	type Event = Event;
+	type Balance = u64;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type OnReceived = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = DORA;
+}
+
+impl orml_currencies::Trait for Test {
+	type Event = Event;
+	type MultiCurrency = orml_tokens::Module<Test>;
+	type NativeCurrency = orml_currencies::BasicCurrencyAdapter<Test, pallet_balances::Module<Test>, Amount, u64>;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type WeightInfo = ();
+}
+
 impl pallet_timestamp::Trait for Test {
     type Moment = u64;
     type OnTimestampSet = ();
@@ -96,12 +170,12 @@ impl pallet_timestamp::Trait for Test {
 
 impl Config for Test {
 	type ModuleId = MolochV2ModuleId;
-    // The Balances pallet implements the ReservableCurrency trait.
-    // https://substrate.dev/rustdocs/v2.0.0/pallet_balances/index.html#implementations-2
-    type Currency = pallet_balances::Module<Test>;
-
-    // No action is taken when deposits are forfeited.
-    type Slashed = ();
+	type CurrencyId = CurrencyId;
+	// native DORA goes through Balances, other tokens through orml_tokens
+	type Currency = orml_currencies::Module<Test>;
+	type NativeCurrencyId = GetNativeCurrencyId;
+	// periods are counted in blocks, tests move on with `System::set_block_number`
+	type Clock = crate::clock::BlockNumberClock<Test>;
 
 	type Event = Event;
 
@@ -119,10 +193,26 @@ impl Config for Test {
 	// maximum number of shares
 	type MaxShares = MaxShares;
 
+	// maximum number of whitelisted tokens
+	type MaxTokens = MaxTokens;
+
+	type AutoProcessBudget = AutoProcessBudget;
+	type AutoProcessReward = AutoProcessReward;
+
+	type Call = Call;
+	type CallFilter = ActionCallFilter;
+	type MaxActionWeight = MaxActionWeight;
+
+	// a test signature is valid for the message it carries and its signer
+	type OffchainSignature = sp_runtime::testing::TestSignature;
+	type SigningPublicKey = sp_runtime::testing::UintAuthorityId;
+
+	type WeightInfo = ();
 }
 
 pub type System = frame_system::Module<Test>;
 pub type Balances = pallet_balances::Module<Test>;
+pub type Tokens = orml_tokens::Module<Test>;
 pub type MolochV2 = Module<Test>;
 pub type Timestamp = pallet_timestamp::Module<Test>;
 
@@ -134,6 +224,30 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		// Total issuance will be 1000 with internal account initialized at ED.
 		balances: vec![(0, 3000), (1, 3000), (2, 3000), (3, 3000), (4, 3000), (5, 3000)],
 	}.assimilate_storage(&mut t).unwrap();
+	orml_tokens::GenesisConfig::<Test>{
+		endowed_accounts: (0..6).flat_map(|who| vec![(who, USDT, 3000), (who, BTC, 3000), (who, ETH, 3000)]).collect(),
+	}.assimilate_storage(&mut t).unwrap();
 	system::GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
 	t.into()
-}
\ No newline at end of file
+}
+// Build genesis storage with a DAO summoned at genesis.
+pub fn new_test_ext_with_summoners(summoners: Vec<(u64, u128, u128)>) -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test>{
+		balances: vec![(0, 3000), (1, 3000), (2, 3000), (3, 3000), (4, 3000), (5, 3000)],
+	}.assimilate_storage(&mut t).unwrap();
+	orml_tokens::GenesisConfig::<Test>{
+		endowed_accounts: (0..6).flat_map(|who| vec![(who, USDT, 3000), (who, BTC, 3000), (who, ETH, 3000)]).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test>{
+		summoners: summoners,
+		approved_tokens: vec![DORA, USDT],
+		period_duration: 10,
+		voting_period_length: 2,
+		grace_period_length: 2,
+		dilution_bound: 1,
+		proposal_deposit: 100,
+		processing_reward: 50,
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
@@ -1,10 +1,19 @@
 use crate::{Error, mock::*};
 use frame_support::{assert_ok, assert_noop};
-use super::RawEvent;
+use sp_runtime::{Perbill, DispatchError, testing::TestSignature};
+use frame_support::codec::Encode;
+use super::{RawEvent, DaoId, ProposalKind, ProposalStatus, FailureReason, VotingThreshold, VotingMode, Conviction, ShareLock, VoteMessage, SignedVote, VestingSchedule, PayoutStream, ParameterChange, CallCategory, Releases, Proposals, StorageVersion, NextToProcess, SummonTime,
+            ShareCheckpoints, TotalShareCheckpoints, PayoutStreams, Member};
+use crate::clock::{Clock, TimestampClock};
+use crate::migrations::{self, ProposalV1, ProposalV2, ProposalV6, EscrowV1};
+use frame_support::{Blake2_128Concat, StorageHasher, storage::migration::{StorageIterator, get_storage_value, put_storage_value}};
+use frame_support::{StorageMap, StorageDoubleMap, StorageValue, traits::{OnInitialize, OnRuntimeUpgrade, Currency, Get}};
+use sp_runtime::traits::AccountIdConversion;
 use sp_std::convert::{TryInto};
+use orml_traits::MultiCurrency;
 
 
-fn last_event() -> RawEvent<u64, u64> {
+fn last_event() -> RawEvent<u64, u64, CurrencyId, u64> {
 	System::events().into_iter().map(|r| r.event)
 		.filter_map(|e| {
 			if let Event::moloch_v2(inner) = e { Some(inner) } else { None }
@@ -13,9 +22,9 @@ fn last_event() -> RawEvent<u64, u64> {
 		.unwrap()
 }
 
-/// A helper function to summon moloch for each test case
-fn summon_with(initial_member: u64) {
-	// in seconds
+/// A helper function to summon moloch for each test case, returns the id of the new DAO
+fn summon_with(initial_member: u64) -> DaoId {
+	// in blocks
 	let period_duration = 10;
 	let voting_period_length = 2;
 	let grace_period_length = 2;
@@ -25,6 +34,9 @@ fn summon_with(initial_member: u64) {
 
 	let _ = MolochV2::summon(
 		Origin::signed(initial_member),
+		vec![(initial_member, 1, 0)],
+		vec![DORA, USDT],
+		0,
 		period_duration,
 		voting_period_length,
 		grace_period_length,
@@ -32,12 +44,27 @@ fn summon_with(initial_member: u64) {
 		proposal_deposit,
 		processing_reward
 	);
+	MolochV2::dao_count() - 1
 }
 
-/// Simulate a scenario that a member is proposed in jail
-fn put_in_jail(initial_member: u64, jailed_member: u64) {
+/// A DAO of member 1 with 6 shares and member 2 with 4 shares, where the proposals 0 and 1 of the
+/// applicants 3 and 4 for a share each are sponsored and open for voting
+fn two_proposals_voting() -> DaoId {
+	System::set_block_number(1);
+	assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 6, 0), (2, 4, 0)], vec![DORA], 0, 10, 2, 2, 1, 100, 50));
+	let dao_id = MolochV2::dao_count() - 1;
+	for applicant in 3..5 {
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(applicant), dao_id, applicant, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, applicant as u128 - 3));
+	}
+	System::set_block_number(21);
+	dao_id
+}
+
+/// Simulate a scenario that a member is proposed in jail, returns the id of the DAO
+fn put_in_jail(initial_member: u64, jailed_member: u64) -> DaoId {
 	// initial a DAO first
-	summon_with(initial_member);
+	let dao_id = summon_with(initial_member);
 
 	// submit a proposal
 	let tribute_offered = 50;
@@ -48,50 +75,53 @@ fn put_in_jail(initial_member: u64, jailed_member: u64) {
 	let mut proposal_idx = 0;
 
 	let _ = MolochV2::submit_proposal(
-		Origin::signed(jailed_member), 
+		Origin::signed(jailed_member),
+		dao_id,
 		jailed_member, 
 		tribute_offered,
+		DORA,
 		shares_requested,
 		loot_requested,
 		payment_requested,
+		DORA,
 		detail.clone()
 	);
 	
 	// sponsor it
-	assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), proposal_idx));
-	// set the timestamp to make voting period effect
-	let now = Timestamp::now();
-	let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration() * 1000 * 2).ok().unwrap();
-	Timestamp::set_timestamp(now + period_duration);
+	assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, proposal_idx));
+	// move on to make voting period effect
+	let now = System::block_number();
+	let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+	System::set_block_number(now + period_duration);
 	// vote yes
-	assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), proposal_idx, 1));
+	assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, proposal_idx, 1));
 	 
 	// pass grace period
-	Timestamp::set_timestamp(now + period_duration * 4);
+	System::set_block_number(now + period_duration * 4);
 	let processor = 0;
-	assert_ok!(MolochV2::process_proposal(Origin::signed(processor), proposal_idx));
+	assert_ok!(MolochV2::process_proposal(Origin::signed(processor), dao_id, proposal_idx));
 	
 	// propose himself to kick
-	assert_ok!(MolochV2::submit_guild_kick_proposal(Origin::signed(jailed_member), jailed_member, detail.clone()));
+	assert_ok!(MolochV2::submit_guild_kick_proposal(Origin::signed(jailed_member), dao_id, jailed_member, detail.clone()));
 	proposal_idx = proposal_idx + 1;
 	// sponsor it
-	assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), proposal_idx));
-	// set the timestamp to make voting period effect
-	let now = Timestamp::now();
-	let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration() * 1000 * 2).ok().unwrap();
-	Timestamp::set_timestamp(now + period_duration);
+	assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, proposal_idx));
+	// move on to make voting period effect
+	let now = System::block_number();
+	let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+	System::set_block_number(now + period_duration);
 	// vote yes
-	assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), proposal_idx, 1));
-	Timestamp::set_timestamp(now + period_duration * 4);
+	assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, proposal_idx, 1));
+	System::set_block_number(now + period_duration * 4);
 	let processor = 0;
-	assert_ok!(MolochV2::process_guild_kick_proposal(Origin::signed(processor), proposal_idx));
-	
+	assert_ok!(MolochV2::process_guild_kick_proposal(Origin::signed(processor), dao_id, proposal_idx));
+	dao_id
 }
 
 #[test]
 fn summon_works() {
 	new_test_ext().execute_with(|| {
-		// in seconds
+		// in blocks
 		let period_duration = 10;
 		let voting_period_length = 2;
 		let grace_period_length = 2;
@@ -99,25 +129,83 @@ fn summon_works() {
 		let proposal_deposit = 100;
 		let processing_reward = 50;
 
-		assert_ok!(MolochV2::summon(Origin::signed(1), period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward));
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![DORA], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward));
+		let dao_id = 0;
+		assert_eq!(MolochV2::dao_count(), 1);
 		// check the constants
-		assert_eq!(MolochV2::period_duration(), period_duration);
-		assert_eq!(MolochV2::voting_period_length(), voting_period_length);
-		assert_eq!(MolochV2::grace_period_length(), grace_period_length);
-		assert_eq!(MolochV2::dilution_bound(), dilution_bound);
-		assert_eq!(MolochV2::proposal_deposit(), proposal_deposit);
-		assert_eq!(MolochV2::processing_reward(), processing_reward);
+		assert_eq!(MolochV2::period_duration(dao_id), period_duration);
+		assert_eq!(MolochV2::voting_period_length(dao_id), voting_period_length);
+		assert_eq!(MolochV2::grace_period_length(dao_id), grace_period_length);
+		assert_eq!(MolochV2::dilution_bound(dao_id), dilution_bound);
+		assert_eq!(MolochV2::proposal_deposit(dao_id), proposal_deposit);
+		assert_eq!(MolochV2::processing_reward(dao_id), processing_reward);
 
 		// check the shares and member
-		assert_eq!(MolochV2::totoal_shares(), 1);
-		assert_eq!(MolochV2::members(1).exists, true);
+		assert_eq!(MolochV2::totoal_shares(dao_id), 1);
+		assert_eq!(MolochV2::members(dao_id, 1).exists, true);
+	});
+}
+
+#[test]
+fn genesis_summon_works() {
+	new_test_ext_with_summoners(vec![(1, 10, 0), (2, 5, 5)]).execute_with(|| {
+		let dao_id = 0;
+		assert_eq!(MolochV2::dao_count(), 1);
+		assert_eq!(MolochV2::period_duration(dao_id), 10);
+		assert_eq!(MolochV2::proposal_deposit(dao_id), 100);
+		assert_eq!(MolochV2::members(dao_id, 1).shares, 10);
+		assert_eq!(MolochV2::members(dao_id, 2).loot, 5);
+		assert_eq!(MolochV2::totoal_shares(dao_id), 15);
+		// the DAO's account is created at genesis
+		assert_eq!(Balances::free_balance(&MolochV2::account_id(dao_id)), 1);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 0);
+
+		// the genesis DAO works as a summoned one
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 50, DORA, 5, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		// and the next summon gets a fresh id
+		assert_eq!(summon_with(4), 1);
+	});
+
+	// no DAO is summoned without summoners
+	new_test_ext_with_summoners(vec![]).execute_with(|| {
+		assert_eq!(MolochV2::dao_count(), 0);
+	});
+}
+
+#[test]
+fn summon_multiple_daos_works() {
+	new_test_ext().execute_with(|| {
+		let first_dao = summon_with(1);
+		let second_dao = summon_with(2);
+		assert_eq!(first_dao, 0);
+		assert_eq!(second_dao, 1);
+		assert_eq!(MolochV2::dao_count(), 2);
+
+		// each DAO keeps its own members and shares
+		assert_eq!(MolochV2::members(first_dao, 1).exists, true);
+		assert_eq!(MolochV2::members(first_dao, 2).exists, false);
+		assert_eq!(MolochV2::members(second_dao, 2).exists, true);
+		assert_eq!(MolochV2::totoal_shares(first_dao), 1);
+		assert_eq!(MolochV2::totoal_shares(second_dao), 1);
+
+		// and its own guild bank and custody account
+		assert_ne!(MolochV2::account_id(first_dao), MolochV2::account_id(second_dao));
+		assert_ne!(MolochV2::custody_account(first_dao), MolochV2::custody_account(second_dao));
+		assert_eq!(Balances::free_balance(&MolochV2::account_id(second_dao)), 1);
+
+		// proposals can not be submitted to a DAO which is not summoned yet
+		assert_noop!(
+			MolochV2::submit_proposal(Origin::signed(3), 2, 3, 50, DORA, 5, 0, 0, DORA, b"test_proposal".to_vec()),
+			Error::<Test>::DaoNotExist
+		);
 	});
 }
 
 #[test]
 fn summon_failed_validation() {
 	new_test_ext().execute_with(|| {
-		// in seconds
+		// in blocks
 		let period_duration = 10;
 		let voting_period_length = 2;
 		let grace_period_length = 2;
@@ -129,6 +217,9 @@ fn summon_failed_validation() {
 		assert_noop!(
 			MolochV2::summon(
 				Origin::signed(1),
+				vec![(1, 1, 0)],
+				vec![DORA],
+				0,
 				period_duration,
 				voting_period_length,
 				grace_period_length,
@@ -138,6 +229,71 @@ fn summon_failed_validation() {
 			),
 			Error::<Test>::NoEnoughProposalDeposit
 		);
+
+		let processing_reward = 50;
+		// periods can not be empty
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![DORA], 0, 0, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::PeriodDurationIsZero
+		);
+		// at least one summoner is required
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![], vec![DORA], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::NoSummoner
+		);
+		// a summoner can only be listed once
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 1, 0), (1, 2, 0)], vec![DORA], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::DuplicateSummoner
+		);
+		// someone should hold voting shares
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 0, 10)], vec![DORA], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::NoEnoughShares
+		);
+		// at least the deposit token should be whitelisted
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::NoApprovedToken
+		);
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![DORA, DORA], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::DuplicateToken
+		);
+		assert_noop!(
+			MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![DORA, USDT, BTC, ETH], 0, period_duration, voting_period_length, grace_period_length, dilution_bound, proposal_deposit, processing_reward),
+			Error::<Test>::TooManyTokens
+		);
+	});
+}
+
+#[test]
+fn summon_with_multiple_summoners_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_tribute = 1000;
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 10, 0), (2, 5, 5), (3, 0, 20)], vec![DORA], initial_tribute, 10, 2, 2, 1, 100, 50));
+		let dao_id = 0;
+
+		// the founders get their cap table right away
+		assert_eq!(MolochV2::members(dao_id, 1).shares, 10);
+		assert_eq!(MolochV2::members(dao_id, 2).shares, 5);
+		assert_eq!(MolochV2::members(dao_id, 2).loot, 5);
+		assert_eq!(MolochV2::members(dao_id, 3).loot, 20);
+		assert_eq!(MolochV2::address_of_delegate(dao_id, 3), 3);
+		assert_eq!(MolochV2::totoal_shares(dao_id), 15);
+		assert_eq!(MolochV2::totoal_loot(dao_id), 25);
+		assert_eq!(last_event(), RawEvent::SummonComplete(dao_id, 3, 0));
+
+		// the caller pays the initial tribute and the existential deposit of the DAO's account
+		assert_eq!(Balances::free_balance(&MolochV2::account_id(dao_id)), initial_tribute + 1);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), initial_tribute.into());
+		assert_eq!(Balances::free_balance(1), 3000 - initial_tribute - 1);
+
+		// another summon never touches the live DAO
+		assert_ok!(MolochV2::summon(Origin::signed(4), vec![(4, 1, 0)], vec![DORA], 0, 10, 2, 2, 1, 100, 50));
+		assert_eq!(MolochV2::totoal_shares(dao_id), 15);
+		assert_eq!(MolochV2::members(dao_id, 1).shares, 10);
 	});
 }
 
@@ -147,7 +303,7 @@ fn submit_proposal_works() {
 		// IMPORTANT, event won't emit in block 0
 		System::set_block_number(1);
 		let initial_member = 1;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 
 		// failed when member propose for applicant who did not deposit in custody account
 		let tribute_offered = 50;
@@ -159,16 +315,25 @@ fn submit_proposal_works() {
 
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(1), 
+				Origin::signed(1),
+				dao_id,
 				applicant, 
 				tribute_offered,
+				DORA,
 				shares_requested,
 				loot_requested,
 				payment_requested,
+				DORA,
 				detail.clone()
 			)
 		);
-		assert_eq!(last_event(), RawEvent::SubmitProposal(0, 1, 1, applicant, tribute_offered.into(), shares_requested));
+		assert_eq!(last_event(), RawEvent::SubmitProposal(dao_id, 0, 1, 1, applicant, tribute_offered.into(), shares_requested));
+
+		// requested shares and loot overflowing u128 are rejected instead of panicking
+		assert_noop!(
+			MolochV2::submit_proposal(Origin::signed(1), dao_id, applicant, 0, DORA, u128::MAX, 1, 0, DORA, detail.clone()),
+			Error::<Test>::SharesOverFlow
+		);
 	});
 }
 
@@ -178,7 +343,7 @@ fn add_member_works() {
 		// IMPORTANT, event won't emit in block 0
 		System::set_block_number(1);
 		let initial_member = 1;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 
 		// submit a proposal
 		let tribute_offered = 50;
@@ -191,37 +356,42 @@ fn add_member_works() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
 		// need to be sponsored 
-		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), 0));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
 
-		// set the timestamp to make voting period effect
-		let now = Timestamp::now();
-		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration() * 1000 * 2).ok().unwrap();
-		Timestamp::set_timestamp(now + period_duration);
+		// move on to make voting period effect
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
 
 		// vote yes
-		assert_ok!(MolochV2::submit_vote(Origin::signed(1), 0, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
 		 
 		// pass grace period
-		Timestamp::set_timestamp(now + period_duration * 4);
+		System::set_block_number(now + period_duration * 4);
 		let processor = 3;
 		let balance_before = Balances::free_balance(processor);
-		let processing_reward = MolochV2::processing_reward();
-		assert_ok!(MolochV2::process_proposal(Origin::signed(processor), 0));
+		let processing_reward = MolochV2::processing_reward(dao_id);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(processor), dao_id, 0));
 		// make sure the processor get rewarded
+		assert_eq!(MolochV2::user_token_balances((dao_id, processor), DORA), processing_reward.into());
+		assert_ok!(MolochV2::withdraw_balance(Origin::signed(processor), dao_id, DORA, processing_reward));
 		assert_eq!(Balances::free_balance(processor), processing_reward + balance_before);
 
 		// check the applicant has become a member
-		assert_eq!(MolochV2::members(applicant).exists, true);
+		assert_eq!(MolochV2::members(dao_id, applicant).exists, true);
 		
 	});
 }
@@ -231,7 +401,7 @@ fn vote_failed_validation() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		let initial_member = 1;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 
 		// submit a proposal
 		let tribute_offered = 50;
@@ -244,34 +414,37 @@ fn vote_failed_validation() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
 
 		// only member can vote
 		assert_noop!(
-			MolochV2::submit_vote(Origin::signed(2), 0, 1),
+			MolochV2::submit_vote(Origin::signed(2), dao_id, 0, 1),
 			Error::<Test>::NotMember
 		);
 
 		// can not vote a proposal until it's sponsored
 		assert_noop!(
-			MolochV2::submit_vote(Origin::signed(initial_member), 0, 1),
+			MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1),
 			Error::<Test>::ProposalNotExist
 		);
 
 		// sponsor it and continue to vote
-		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), 0));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
 
 		// can not vote a proposal until it's in voting period
 		assert_noop!(
-			MolochV2::submit_vote(Origin::signed(initial_member), 0, 1),
+			MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1),
 			Error::<Test>::ProposalNotStart
 		);
 	});
@@ -282,10 +455,10 @@ fn guild_kick_works() {
 	new_test_ext().execute_with(|| {
 		let initial_member = 1;
 		let jailed_member = 2;
-		put_in_jail(initial_member, jailed_member);
+		let dao_id = put_in_jail(initial_member, jailed_member);
 		// make sure the member is in jail
-		assert_eq!(MolochV2::members(jailed_member).exists, true);
-		assert_eq!(MolochV2::members(jailed_member).jailed_at > 0, true);
+		assert_eq!(MolochV2::members(dao_id, jailed_member).exists, true);
+		assert_eq!(MolochV2::members(dao_id, jailed_member).jailed_at > 0, true);
 	});
 }
 
@@ -294,7 +467,7 @@ fn guild_member_failed() {
 	new_test_ext().execute_with(|| {
 		let initial_member = 1;
 		let jailed_member = 2;
-		put_in_jail(initial_member, jailed_member);
+		let dao_id = put_in_jail(initial_member, jailed_member);
 		// submit a proposal
 		let tribute_offered = 50;
 		let shares_requested = 5;
@@ -306,12 +479,15 @@ fn guild_member_failed() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
@@ -319,7 +495,7 @@ fn guild_member_failed() {
 		// member in jailed can not sponsor
 		// NOTE: as our helper functions have submitted 2 proposal, thus ours index is 2
 		assert_noop!(
-			MolochV2::sponsor_proposal(Origin::signed(jailed_member), 2),
+			MolochV2::sponsor_proposal(Origin::signed(jailed_member), dao_id, 2),
 			Error::<Test>::MemberInJail
 		);
 	});
@@ -330,7 +506,7 @@ fn abort_works() {
 	new_test_ext().execute_with(|| {
 		System::set_block_number(1);
 		let initial_member = 1;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 
 		// submit a proposal
 		let tribute_offered = 50;
@@ -343,17 +519,20 @@ fn abort_works() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
 
-		assert_ok!(MolochV2::abort(Origin::signed(applicant), 0));
+		assert_ok!(MolochV2::abort(Origin::signed(applicant), dao_id, 0));
 	});
 }
 
@@ -363,7 +542,7 @@ fn abort_failed() {
 		System::set_block_number(1);
 		let initial_member = 1;
 		let naughty_boy = 0;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 
 		// submit a proposal
 		let tribute_offered = 50;
@@ -376,19 +555,22 @@ fn abort_failed() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
 
 		// only proposer can abort
 		assert_noop!(
-			MolochV2::abort(Origin::signed(naughty_boy), 0),
+			MolochV2::abort(Origin::signed(naughty_boy), dao_id, 0),
 			Error::<Test>::NotProposalProposer
 		);
 
@@ -400,8 +582,8 @@ fn rage_kick_works() {
 	new_test_ext().execute_with(|| {
 		let initial_member = 1;
 		let jailed_member = 2;
-		put_in_jail(initial_member, jailed_member);
-		assert_ok!(MolochV2::rage_kick(Origin::signed(0), jailed_member));
+		let dao_id = put_in_jail(initial_member, jailed_member);
+		assert_ok!(MolochV2::rage_kick(Origin::signed(0), dao_id, jailed_member));
 	});
 }
 
@@ -410,9 +592,11 @@ fn rage_kick_failed() {
 	new_test_ext().execute_with(|| {
 		// add a member and not put in jail, 1=initial_member, 2=new_member
 		add_member_works();
+		// the DAO summoned by the case above
+		let dao_id = 0;
 		// anyone can call rage_kick
 		assert_noop!(
-			MolochV2::rage_kick(Origin::signed(0), 2),
+			MolochV2::rage_kick(Origin::signed(0), dao_id, 2),
 			Error::<Test>::MemberNotInJail
 		);
 		
@@ -424,10 +608,10 @@ fn update_delegate_validation() {
 	new_test_ext().execute_with(|| {
 		let initial_member = 1;
 		let delegate = 3;
-		summon_with(initial_member);
+		let dao_id = summon_with(initial_member);
 		// anyone can call rage_kick
 		assert_noop!(
-			MolochV2::rage_kick(Origin::signed(0), 2),
+			MolochV2::rage_kick(Origin::signed(0), dao_id, 2),
 			Error::<Test>::MemberNotInJail
 		);
 		// submit a proposal
@@ -442,30 +626,1440 @@ fn update_delegate_validation() {
 		// a non-member can submit
 		assert_ok!(
 			MolochV2::submit_proposal(
-				Origin::signed(applicant), 
+				Origin::signed(applicant),
+				dao_id,
 				applicant, 
 				tribute_offered, 
+				DORA,
 				shares_requested, 
 				loot_requested,
 				payment_requested, 
+				DORA,
 				detail
 			)
 		);
 		// sponsor it
-		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), proposal_idx));
-		// set the timestamp to make voting period effect
-		let now = Timestamp::now();
-		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration() * 1000 * 2).ok().unwrap();
-		Timestamp::set_timestamp(now + period_duration);
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, proposal_idx));
+		// move on to make voting period effect
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
 		
 		// change delegate
-		assert_ok!(MolochV2::update_delegate(Origin::signed(initial_member), delegate));
+		assert_ok!(MolochV2::update_delegate(Origin::signed(initial_member), dao_id, delegate));
 		// negative case as the voting rights have been delegated
 		assert_noop!(
-			MolochV2::submit_vote(Origin::signed(initial_member), proposal_idx, 1),
+			MolochV2::submit_vote(Origin::signed(initial_member), dao_id, proposal_idx, 1),
 			Error::<Test>::NotMember
 		);
 		// positive case, use delegate to vote
-		assert_ok!(MolochV2::submit_vote(Origin::signed(delegate), proposal_idx, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(delegate), dao_id, proposal_idx, 1));
+	});
+}
+#[test]
+fn submit_proposal_with_unlisted_token_failed() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		let detail = b"test_proposal".to_vec();
+		// BTC is not whitelisted, neither as tribute nor as payment
+		assert_noop!(
+			MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, BTC, 5, 0, 0, DORA, detail.clone()),
+			Error::<Test>::TokenNotWhitelisted
+		);
+		assert_noop!(
+			MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, DORA, 5, 0, 10, BTC, detail.clone()),
+			Error::<Test>::TokenNotWhitelisted
+		);
+	});
+}
+
+#[test]
+fn whitelist_proposal_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		let detail = b"whitelist_btc".to_vec();
+
+		// an approved token can not be proposed again
+		assert_noop!(
+			MolochV2::submit_whitelist_proposal(Origin::signed(2), dao_id, USDT, detail.clone()),
+			Error::<Test>::TokenAlreadyWhitelisted
+		);
+		assert_ok!(MolochV2::submit_whitelist_proposal(Origin::signed(2), dao_id, BTC, detail.clone()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		assert_eq!(MolochV2::proposed_to_whitelist(dao_id, BTC), true);
+
+		// the same token can only be in one sponsored whitelist proposal
+		assert_ok!(MolochV2::submit_whitelist_proposal(Origin::signed(2), dao_id, BTC, detail.clone()));
+		assert_noop!(
+			MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 1),
+			Error::<Test>::TokenAlreadyProposed
+		);
+
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(now + period_duration * 4);
+
+		// standard processing is rejected for whitelist proposals
+		assert_noop!(
+			MolochV2::process_proposal(Origin::signed(3), dao_id, 0),
+			Error::<Test>::NotStandardProposal
+		);
+		assert_ok!(MolochV2::process_whitelist_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::ProcessWhitelistProposal(dao_id, 0, BTC, true));
+		assert_eq!(MolochV2::token_whitelist(dao_id, BTC), true);
+		assert_eq!(MolochV2::proposed_to_whitelist(dao_id, BTC), false);
+		assert_eq!(MolochV2::approved_tokens(dao_id), vec![DORA, USDT, BTC]);
+
+		// BTC can be used as tribute now
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, BTC, 5, 0, 0, DORA, detail.clone()));
+		// the whitelist is full
+		assert_noop!(
+			MolochV2::submit_whitelist_proposal(Origin::signed(2), dao_id, ETH, detail.clone()),
+			Error::<Test>::TooManyTokens
+		);
+	});
+}
+
+#[test]
+fn tribute_in_stablecoin_works() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		let applicant = 2;
+		let tribute_offered = 1000;
+		let detail = b"test_proposal".to_vec();
+
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(applicant), dao_id, applicant, tribute_offered, USDT, 1, 0, 0, DORA, detail));
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), USDT), tribute_offered.into());
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(now + period_duration * 4);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+
+		// the tribute is moved to the guild bank
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), USDT), 0);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), tribute_offered.into());
+		assert_eq!(Tokens::free_balance(USDT, &MolochV2::account_id(dao_id)), tribute_offered);
+
+		// ragequit credits a fair share of every whitelisted token
+		assert_ok!(MolochV2::rage_quit(Origin::signed(applicant), dao_id, 1, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, applicant), USDT), (tribute_offered / 2).into());
+		assert_ok!(MolochV2::withdraw_balances(Origin::signed(applicant), dao_id, vec![DORA, USDT], vec![0, 0], true));
+		assert_eq!(Tokens::free_balance(USDT, &applicant), 3000 - tribute_offered / 2);
+		let mut balances = MolochV2::user_balances(dao_id, applicant);
+		balances.sort();
+		assert_eq!(balances, vec![(DORA, 0), (USDT, 0)]);
+	});
+}
+
+#[test]
+fn funding_proposal_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_member = 1;
+		let applicant = 2;
+		let initial_tribute = 1000;
+		let payment_requested = 400;
+		assert_ok!(MolochV2::summon(Origin::signed(initial_member), vec![(initial_member, 1, 0)], vec![DORA, USDT], initial_tribute, 10, 2, 2, 1, 100, 50));
+		let dao_id = 0;
+
+		// ask for a grant without any tribute
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(applicant), dao_id, applicant, 0, DORA, 0, 0, payment_requested, DORA, b"grant".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(now + period_duration * 4);
+
+		let applicant_before = Balances::free_balance(applicant);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+		assert!(System::events().iter().any(|r| r.event == Event::moloch_v2(RawEvent::PaymentDisbursed(dao_id, 0, applicant, DORA, payment_requested.into()))));
+		// the applicant is paid
+		assert_eq!(MolochV2::user_token_balances((dao_id, applicant), DORA), payment_requested.into());
+		assert_ok!(MolochV2::withdraw_balance(Origin::signed(applicant), dao_id, DORA, payment_requested));
+		assert_eq!(Balances::free_balance(applicant), applicant_before + payment_requested);
+		// the payment leaves the guild bank
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), (initial_tribute - payment_requested).into());
+
+		// so ragequit only shares what is left, on top of the deposit returned to the sponsor
+		assert_ok!(MolochV2::rage_quit(Origin::signed(initial_member), dao_id, 1, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, initial_member), DORA), (initial_tribute - payment_requested + 50).into());
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 0);
+	});
+}
+
+#[test]
+fn funding_proposal_over_guild_bank_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_member = 1;
+		let applicant = 2;
+		let dao_id = summon_with(initial_member);
+
+		// the guild bank holds no USDT at all
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(applicant), dao_id, applicant, 0, DORA, 0, 0, 400, USDT, b"grant".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(now + period_duration * 4);
+
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::ProcessProposal(dao_id, 0, applicant, applicant, 0, 0, false));
+		assert_eq!(Tokens::free_balance(USDT, &applicant), 3000);
+		assert_eq!(MolochV2::members(dao_id, applicant).exists, false);
+	});
+}
+
+#[test]
+fn withdraw_balance_failed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		// the tribute of an aborted proposal is credited back to the proposer
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(initial_member), dao_id, 2, 1000, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::abort(Origin::signed(initial_member), dao_id, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, initial_member), DORA), 1000);
+
+		// can not withdraw more than the internal balance
+		assert_noop!(
+			MolochV2::withdraw_balance(Origin::signed(initial_member), dao_id, DORA, 1001),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_noop!(
+			MolochV2::withdraw_balance(Origin::signed(2), dao_id, DORA, 1),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_noop!(
+			MolochV2::withdraw_balances(Origin::signed(initial_member), dao_id, vec![DORA, USDT], vec![1], false),
+			Error::<Test>::InvalidWithdrawal
+		);
+
+		// withdraw part of it, the rest stays in the ledger
+		assert_ok!(MolochV2::withdraw_balance(Origin::signed(initial_member), dao_id, DORA, 400));
+		assert_eq!(last_event(), RawEvent::Withdraw(dao_id, initial_member, DORA, 400));
+		assert_eq!(MolochV2::user_token_balances((dao_id, initial_member), DORA), 600);
+		assert_eq!(Balances::free_balance(&MolochV2::account_id(dao_id)), 601);
+	});
+}
+
+#[test]
+fn escrow_refund_to_payer_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let initial_member = 1;
+		let applicant = 2;
+		let proposer = 3;
+		let processor = 4;
+		let dao_id = summon_with(initial_member);
+
+		// the proposer pays the tribute on behalf of the applicant
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(proposer), dao_id, applicant, 500, USDT, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		let escrow = MolochV2::proposal_escrow(dao_id, 0);
+		assert_eq!(escrow.tribute_payer, proposer);
+		assert_eq!(escrow.tribute, 500);
+		assert_eq!(escrow.deposit_payer, initial_member);
+		assert_eq!(escrow.deposit, 100);
+
+		// pending tribute and deposit are not part of the guild bank, so can not be ragequitted
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), USDT), 500);
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), DORA), 100);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), 0);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 0);
+
+		// vote no
+		let now = System::block_number();
+		let period_duration = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id) * 2).ok().unwrap();
+		System::set_block_number(now + period_duration);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 2));
+		System::set_block_number(now + period_duration * 4);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(processor), dao_id, 0));
+
+		// the tribute goes back to the proposer, the deposit less the reward to the sponsor
+		assert_eq!(MolochV2::user_token_balances((dao_id, proposer), USDT), 500);
+		assert_eq!(MolochV2::user_token_balances((dao_id, applicant), USDT), 0);
+		assert_eq!(MolochV2::user_token_balances((dao_id, initial_member), DORA), 50);
+		assert_eq!(MolochV2::user_token_balances((dao_id, processor), DORA), 50);
+		// and the escrow is cleared
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), USDT), 0);
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), DORA), 0);
+		assert_eq!(MolochV2::proposal_escrow(dao_id, 0).tribute, 0);
+		assert_eq!(MolochV2::proposal_escrow(dao_id, 0).deposit, 0);
+	});
+}
+
+#[test]
+fn proposal_status_works() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, DORA, 5, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::submit_whitelist_proposal(Origin::signed(2), dao_id, BTC, b"whitelist_btc".to_vec()));
+		assert_eq!(MolochV2::proposals(dao_id, 0).kind, ProposalKind::Standard);
+		assert_eq!(MolochV2::proposals(dao_id, 1).kind, ProposalKind::Whitelist);
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::Submitted);
+
+		// the aborted one is cancelled
+		assert_ok!(MolochV2::abort(Origin::signed(2), dao_id, 1));
+		assert_eq!(MolochV2::proposal_status(dao_id, 1), ProposalStatus::Cancelled);
+
+		// sponsored, but the voting period starts in the next period
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::Sponsored);
+
+		let now = System::block_number();
+		let period = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id)).ok().unwrap();
+		System::set_block_number(now + period);
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::Voting);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(now + period * 3);
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::Grace);
+		System::set_block_number(now + period * 5);
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::ReadyToProcess);
+
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(MolochV2::proposal_status(dao_id, 0), ProposalStatus::Processed { passed: true });
+		assert!(MolochV2::proposals(dao_id, 0).did_pass());
+	});
+}
+
+#[test]
+fn runtime_api_queries_work() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		assert_eq!(MolochV2::current_period(0), None);
+		let dao_id = summon_with(initial_member);
+		assert_eq!(MolochV2::current_period(dao_id), Some(0));
+		assert_eq!(MolochV2::member(dao_id, initial_member).map(|m| m.shares), Some(1));
+		assert!(MolochV2::member(dao_id, 2).is_none());
+
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, DORA, 5, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 50, USDT, 5, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 1));
+		// the page stops at the last proposal
+		let page = MolochV2::proposals_page(dao_id, 0, 10);
+		assert_eq!(page.len(), 2);
+		assert_eq!((page[0].0, page[0].2), (0, ProposalStatus::Submitted));
+		assert_eq!((page[1].0, page[1].1.applicant, page[1].2), (1, 3, ProposalStatus::Sponsored));
+		assert_eq!(MolochV2::proposals_page(dao_id, 1, 1).len(), 1);
+		assert!(MolochV2::proposals_page(dao_id, 2, 10).is_empty());
+		assert_eq!(MolochV2::proposal_queue(dao_id), vec![1]);
+
+		let now = System::block_number();
+		let period = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id)).ok().unwrap();
+		System::set_block_number(now + period);
+		assert_eq!(MolochV2::current_period(dao_id), Some(1));
+		assert_eq!(MolochV2::member_vote(dao_id, 1, initial_member), None);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		assert_eq!(MolochV2::member_vote(dao_id, 1, initial_member), Some(1));
+		assert_eq!(MolochV2::proposals_page(dao_id, 1, 1)[0].2, ProposalStatus::Voting);
+
+		System::set_block_number(now + period * 5);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(initial_member), dao_id, 0));
+		// the tribute is in the guild bank
+		assert_eq!(MolochV2::guild_bank_balances(dao_id), vec![(DORA, 0), (USDT, 50)]);
+	});
+}
+
+#[test]
+fn preview_apis_work() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 50, DORA, 5, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_noop!(MolochV2::preview_outcome(dao_id, 1), Error::<Test>::ProposalNotExist);
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(Some(FailureReason::NotEnoughYesVotes)));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+
+		let now = System::block_number();
+		let period = TryInto::<u64>::try_into(MolochV2::period_duration(dao_id)).ok().unwrap();
+		System::set_block_number(now + period);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(None));
+		// still in voting period
+		assert_noop!(MolochV2::preview_process(dao_id, 0), Error::<Test>::ProposalNotReady);
+		assert_noop!(MolochV2::preview_process(dao_id, 1), Error::<Test>::ProposalNotExist);
+
+		System::set_block_number(now + period * 5);
+		let preview = MolochV2::preview_process(dao_id, 0).unwrap();
+		assert_eq!((preview.passed, preview.failure, preview.reward_token, preview.reward), (true, None, DORA, 50));
+		// nothing is written by the previews
+		assert_eq!(MolochV2::proposals(dao_id, 0).status, ProposalStatus::Sponsored);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 3), DORA), preview.reward);
+		assert!(MolochV2::proposals(dao_id, 0).did_pass());
+
+		// the guild bank holds 50 DORA for 6 shares
+		assert_noop!(MolochV2::ragequit_payout(dao_id, &initial_member, 2, 0), Error::<Test>::NoEnoughShares);
+		assert_noop!(MolochV2::ragequit_payout(dao_id, &initial_member, 0, 1), Error::<Test>::NoEnoughLoot);
+		let payout = MolochV2::ragequit_payout(dao_id, &initial_member, 1, 0).unwrap();
+		assert_eq!(payout, vec![(DORA, 8), (USDT, 0)]);
+		let balance = MolochV2::user_token_balances((dao_id, initial_member), DORA);
+		assert_ok!(MolochV2::rage_quit(Origin::signed(initial_member), dao_id, 1, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, initial_member), DORA), balance + 8);
+	});
+}
+
+#[test]
+fn migrate_single_dao_layout_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		// storage of the single-DAO layout, its keys have no `DaoId`
+		put_storage_value(b"MolochV2", b"SummonTime", &[], 5_000u64);
+		put_storage_value(b"MolochV2", b"PeriodDuration", &[], 10u32);
+		put_storage_value(b"MolochV2", b"VotingPeriodLength", &[], 2u128);
+		put_storage_value(b"MolochV2", b"GracePeriodLength", &[], 2u128);
+		put_storage_value(b"MolochV2", b"DilutionBound", &[], 1u128);
+		put_storage_value(b"MolochV2", b"ProposalDeposit", &[], 100u64);
+		put_storage_value(b"MolochV2", b"ProcessingReward", &[], 50u64);
+		put_storage_value(b"MolochV2", b"TotalShares", &[], 10u128);
+		put_storage_value(b"MolochV2", b"TotalLoot", &[], 0u128);
+		put_storage_value(b"MolochV2", b"ProposalCount", &[], 3u128);
+		put_storage_value(b"MolochV2", b"ProposalQueue", &[], vec![0u128, 1]);
+		for (who, shares) in [(1u64, 6u128), (2, 4)].iter() {
+			let member = Member { shares: *shares, exists: true, delegate_key: *who, ..Default::default() };
+			put_storage_value(b"MolochV2", b"Members", &Blake2_128Concat::hash(&who.encode()), member);
+			put_storage_value(b"MolochV2", b"AddressOfDelegates", &Blake2_128Concat::hash(&who.encode()), *who);
+		}
+		// processed, sponsored with a tribute in custody and cancelled
+		let proposals = [
+			ProposalV1 { proposer: 2, applicant: 2, sponsor: 1, flags: [true, true, true, false, false, false], yes_votes: 6, ..Default::default() },
+			ProposalV1 { proposer: 3, applicant: 3, sponsor: 1, tribute_offered: 50, shares_requested: 1, flags: [true, false, false, false, false, false], starting_period: 1, ..Default::default() },
+			ProposalV1 { proposer: 4, applicant: 4, flags: [false, false, false, true, false, false], ..Default::default() },
+		];
+		for (proposal_id, proposal) in proposals.iter().enumerate() {
+			put_storage_value(b"MolochV2", b"Proposals", &Blake2_128Concat::hash(&(proposal_id as u128).encode()), proposal.clone());
+		}
+		let vote_key = [Blake2_128Concat::hash(&0u128.encode()), Blake2_128Concat::hash(&1u64.encode())].concat();
+		put_storage_value(b"MolochV2", b"ProposalVotes", &vote_key, 1u8);
+		// the guild bank holds the deposit of the sponsored proposal
+		let old_bank: u64 = MolochV2ModuleId::get().into_account();
+		let old_custody: u64 = MolochV2ModuleId::get().into_sub_account("custody");
+		let _ = Balances::deposit_creating(&old_bank, 1 + 1000 + 100);
+		let _ = Balances::deposit_creating(&old_custody, 1 + 50);
+		StorageVersion::put(Releases::V1_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		// followed by the later migrations in the same upgrade
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		let dao_id = 0;
+		assert_eq!(MolochV2::dao_count(), 1);
+		assert_eq!(MolochV2::approved_tokens(dao_id), vec![DORA]);
+		assert_eq!(MolochV2::summon_time(dao_id), 5);
+		assert_eq!((MolochV2::period_duration(dao_id), MolochV2::proposal_deposit(dao_id), MolochV2::processing_reward(dao_id)), (10, 100, 50));
+		assert_eq!((MolochV2::totoal_shares(dao_id), MolochV2::proposal_count(dao_id)), (10, 3));
+		assert_eq!(MolochV2::members(dao_id, 1).shares, 6);
+		assert_eq!(MolochV2::address_of_delegate(dao_id, 2), 2);
+		assert_eq!(MolochV2::proposal_queue(dao_id), vec![0, 1]);
+		assert_eq!(MolochV2::member_vote(dao_id, 0, 1), Some(1));
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!((proposal.kind, proposal.status), (ProposalKind::Standard, ProposalStatus::Processed { passed: true }));
+		let proposal = MolochV2::proposals(dao_id, 1);
+		assert_eq!((proposal.kind, proposal.status), (ProposalKind::Standard, ProposalStatus::Sponsored));
+		assert_eq!((proposal.tribute_token, proposal.tribute_offered, proposal.voting_period_length), (DORA, 50, 2));
+		assert_eq!(proposal.voting_mode, VotingMode::Linear);
+		assert_eq!(MolochV2::proposals(dao_id, 2).status, ProposalStatus::Cancelled);
+
+		// the funds moved to DAO 0, the tribute and the deposit are escrowed
+		let escrow = MolochV2::proposal_escrow(dao_id, 1);
+		assert_eq!((escrow.tribute_payer, escrow.tribute, escrow.deposit_payer, escrow.deposit), (3, 50, 1, 100));
+		assert_eq!(escrow.processing_reward, 50);
+		assert!(!crate::ProposalEscrows::<Test>::contains_key(dao_id, 2));
+		assert_eq!(Balances::free_balance(&old_bank) + Balances::free_balance(&old_custody), 0);
+		assert_eq!(Balances::free_balance(&MolochV2::account_id(dao_id)), 1 + 1000 + 100 + 1 + 50);
+		assert_eq!(MolochV2::user_token_balances((dao_id, MolochV2::custody_account(dao_id)), DORA), 150);
+		// the existential deposit of the custody account joins the guild bank
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 1001);
+		assert_eq!(get_storage_value::<u128>(b"MolochV2", b"TotalShares", &[]), None);
+		assert_eq!(StorageIterator::<Member<u64>>::new(b"MolochV2", b"Members").count(), 2);
+
+		// the migration only runs once
+		assert_eq!(migrations::migrate_to_v2::<Test>(), 0);
+	});
+}
+
+#[test]
+fn auto_process_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+		for index in 0..2 {
+			assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, index, 1));
+		}
+		// past the grace period of both
+		System::set_block_number(61);
+
+		// disabled by default, and a budget too small for processing does nothing
+		assert_eq!(MolochV2::on_initialize(1), 0);
+		set_auto_process(1, None);
+		MolochV2::on_initialize(1);
+		assert_eq!(MolochV2::proposals(dao_id, 0).status, ProposalStatus::Sponsored);
+
+		// one proposal per DAO and block, the reward goes to the guild bank by default
+		set_auto_process(u64::max_value(), None);
+		assert!(MolochV2::on_initialize(2) > 0);
+		assert!(MolochV2::proposals(dao_id, 0).did_pass());
+		assert_eq!(MolochV2::proposals(dao_id, 1).status, ProposalStatus::Sponsored);
+		assert_eq!(last_event(), RawEvent::ProposalAutoProcessed(dao_id, 0, MolochV2::account_id(dao_id)));
+		// the processing reward
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 50);
+		assert_eq!(MolochV2::next_to_process(dao_id), 1);
+
+		set_auto_process(u64::max_value(), Some(5));
+		MolochV2::on_initialize(3);
+		assert!(MolochV2::proposals(dao_id, 1).did_pass());
+		assert_eq!(MolochV2::user_token_balances((dao_id, 5), DORA), 50);
+		assert_eq!(last_event(), RawEvent::ProposalAutoProcessed(dao_id, 1, 5));
+
+		// nothing left to process
+		MolochV2::on_initialize(4);
+		assert_eq!(MolochV2::next_to_process(dao_id), 2);
+	});
+}
+
+#[test]
+fn migrate_next_to_process_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+		for index in 0..2 {
+			assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, index, 1));
+		}
+		// past the grace period of both
+		System::set_block_number(61);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0));
+		// stored before the first unprocessed proposal was tracked
+		NextToProcess::remove(dao_id);
+		StorageVersion::put(Releases::V2_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		assert_eq!(MolochV2::next_to_process(dao_id), 1);
+		assert_eq!(MolochV2::next_ready_proposal(dao_id), Some((1, ProposalKind::Standard)));
+	});
+}
+
+#[test]
+fn block_number_periods_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		let dao_id = summon_with(1);
+		assert_eq!(MolochV2::summon_time(dao_id), 5);
+		assert_eq!(MolochV2::get_current_period(dao_id), 0);
+		System::set_block_number(25);
+		assert_eq!(MolochV2::get_current_period(dao_id), 2);
+		// a clock behind the summon time stays in period 0
+		System::set_block_number(1);
+		assert_eq!(MolochV2::get_current_period(dao_id), 0);
+
+		// seconds of the timestamp
+		Timestamp::set_timestamp(12_345);
+		assert_eq!(TimestampClock::<Test>::now(), 12);
+	});
+}
+
+#[test]
+fn migrate_summon_time_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		// summoned at 12.345 seconds of the timestamp
+		frame_support::storage::unhashed::put(&SummonTime::hashed_key_for(dao_id), &12_345u64);
+		StorageVersion::put(Releases::V3_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		assert_eq!(MolochV2::summon_time(dao_id), 12);
+
+		// the migration only runs once
+		migrations::migrate_to_v4::<Test>();
+		assert_eq!(MolochV2::summon_time(dao_id), 12);
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn voting_thresholds_work() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+
+		let (quorum, approval) = (Perbill::from_percent(50), Perbill::from_percent(60));
+		assert_noop!(MolochV2::set_voting_threshold(Origin::signed(1), dao_id, ProposalKind::Standard, quorum, approval), DispatchError::BadOrigin);
+		assert_noop!(MolochV2::set_voting_threshold(Origin::root(), dao_id + 1, ProposalKind::Standard, quorum, approval), Error::<Test>::DaoNotExist);
+		assert_ok!(MolochV2::set_voting_threshold(Origin::root(), dao_id, ProposalKind::Standard, quorum, approval));
+		assert_eq!(last_event(), RawEvent::VotingThresholdSet(dao_id, ProposalKind::Standard, quorum, approval));
+		assert_eq!(MolochV2::voting_threshold(dao_id, ProposalKind::Standard), VotingThreshold { quorum, approval });
+		// other kinds are not affected
+		assert_eq!(MolochV2::voting_threshold(dao_id, ProposalKind::GuildKick), VotingThreshold::default());
+
+		// 4 of 10 shares, below the quorum
+		assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, 0, 1));
+		// 6 of 10 shares, the quorum and just the approval threshold
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		System::set_block_number(61);
+
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(Some(FailureReason::QuorumNotReached)));
+		assert_ok!(MolochV2::set_voting_threshold(Origin::root(), dao_id, ProposalKind::Standard, quorum, Perbill::from_percent(70)));
+		assert_eq!(MolochV2::preview_outcome(dao_id, 1), Ok(Some(FailureReason::ApprovalNotReached)));
+		assert_ok!(MolochV2::set_voting_threshold(Origin::root(), dao_id, ProposalKind::Standard, quorum, approval));
+
+		assert_ok!(MolochV2::process_proposal(Origin::signed(5), dao_id, 0));
+		assert!(!MolochV2::proposals(dao_id, 0).did_pass());
+		assert!(System::events().into_iter().any(|r| r.event == Event::moloch_v2(RawEvent::ProposalFailed(dao_id, 0, FailureReason::QuorumNotReached))));
+		assert_ok!(MolochV2::process_proposal(Origin::signed(5), dao_id, 1));
+		assert!(MolochV2::proposals(dao_id, 1).did_pass());
+		assert_eq!(MolochV2::members(dao_id, 4).shares, 1);
+	});
+}
+
+#[test]
+fn votes_are_weighted_by_share_checkpoints() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		assert_eq!(MolochV2::share_checkpoints(dao_id, initial_member), vec![(0, 1)]);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 0), 0);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 1), 1);
+
+		// the member asks for 5 more shares, voting in [1, 3)
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(initial_member), dao_id, initial_member, 0, DORA, 5, 0, 0, DORA, b"more_shares".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		System::set_block_number(10);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		// voting in [4, 6)
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		System::set_block_number(30);
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 1));
+		assert_eq!(MolochV2::proposals(dao_id, 1).starting_period, 4);
+
+		System::set_block_number(50);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(0), dao_id, 0));
+		assert_eq!(MolochV2::members(dao_id, initial_member).shares, 6);
+		assert_eq!(MolochV2::share_checkpoints(dao_id, initial_member), vec![(0, 1), (5, 6)]);
+		assert_eq!(MolochV2::total_share_checkpoints(dao_id), vec![(0, 1), (5, 6)]);
+		// the shares gained in the middle of the voting do not count
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 1, 1));
+		assert_eq!(MolochV2::proposals(dao_id, 1).yes_votes, 1);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 5), 1);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 6), 6);
+		assert_eq!(MolochV2::total_shares_at(dao_id, 6), 6);
+		// no shares before joining
+		assert_eq!(MolochV2::shares_at(dao_id, &3, 6), 0);
+	});
+}
+
+#[test]
+fn share_checkpoints_are_pruned() {
+	new_test_ext().execute_with(|| {
+		let initial_member = 1;
+		let dao_id = summon_with(initial_member);
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(initial_member), dao_id, initial_member, 0, DORA, 5, 0, 0, DORA, b"more_shares".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 0));
+		System::set_block_number(10);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(initial_member), dao_id, 0, 1));
+		System::set_block_number(50);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(0), dao_id, 0));
+		// voting in [6, 8)
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(initial_member), dao_id, 1));
+
+		// the shares when period 6 starts are kept for the unprocessed proposal
+		System::set_block_number(60);
+		assert_ok!(MolochV2::rage_quit(Origin::signed(initial_member), dao_id, 1, 0));
+		assert_eq!(MolochV2::share_checkpoints(dao_id, initial_member), vec![(5, 6), (6, 5)]);
+		assert_eq!(MolochV2::total_share_checkpoints(dao_id), vec![(5, 6), (6, 5)]);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 6), 6);
+
+		// nothing left to weight by the old shares once it is processed
+		System::set_block_number(100);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(0), dao_id, 1));
+		assert_ok!(MolochV2::rage_quit(Origin::signed(initial_member), dao_id, 1, 0));
+		assert_eq!(MolochV2::share_checkpoints(dao_id, initial_member), vec![(10, 4)]);
+		assert_eq!(MolochV2::total_share_checkpoints(dao_id), vec![(10, 4)]);
+		assert_eq!(MolochV2::shares_at(dao_id, &initial_member, 11), 4);
+	});
+}
+
+#[test]
+fn migrate_share_checkpoints_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 6, 0), (2, 4, 0)], vec![DORA], 0, 10, 2, 2, 1, 100, 50));
+		let dao_id = MolochV2::dao_count() - 1;
+		// stored before the shares were checkpointed
+		ShareCheckpoints::<Test>::remove(dao_id, 1);
+		ShareCheckpoints::<Test>::remove(dao_id, 2);
+		TotalShareCheckpoints::remove(dao_id);
+		StorageVersion::put(Releases::V4_0_0);
+		System::set_block_number(50);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		assert_eq!(MolochV2::share_checkpoints(dao_id, 1), vec![(0, 6)]);
+		assert_eq!(MolochV2::share_checkpoints(dao_id, 2), vec![(0, 4)]);
+		assert_eq!(MolochV2::total_shares_at(dao_id, 1), 10);
+	});
+}
+
+#[test]
+fn change_retract_and_abstain_votes_work() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+
+		assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, 1, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, 0, 1));
+		assert_eq!(MolochV2::members(dao_id, 2).highest_index_yes_vote, 1);
+		assert_noop!(MolochV2::submit_vote(Origin::signed(2), dao_id, 1, 1), Error::<Test>::MemberHasVoted);
+		assert_noop!(MolochV2::submit_vote(Origin::signed(2), dao_id, 1, 4), Error::<Test>::InvalidVote);
+
+		// switch to no, the previous yes vote holds back a ragequit again
+		assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, 1, 2));
+		let proposal = MolochV2::proposals(dao_id, 1);
+		assert_eq!((proposal.yes_votes, proposal.no_votes), (0, 4));
+		assert_eq!(MolochV2::member_vote(dao_id, 1, 2), Some(2));
+		assert_eq!(MolochV2::members(dao_id, 2).highest_index_yes_vote, 0);
+
+		assert_ok!(MolochV2::retract_vote(Origin::signed(2), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::VoteRetracted(dao_id, 0, 2, 2));
+		assert_eq!(MolochV2::proposals(dao_id, 0).yes_votes, 0);
+		assert_eq!(MolochV2::member_vote(dao_id, 0, 2), None);
+		assert_noop!(MolochV2::retract_vote(Origin::signed(2), dao_id, 0), Error::<Test>::MemberNotVoted);
+		assert_noop!(MolochV2::retract_vote(Origin::signed(5), dao_id, 0), Error::<Test>::NotMember);
+
+		// abstaining counts toward the quorum but not the approval
+		assert_ok!(MolochV2::set_voting_threshold(Origin::root(), dao_id, ProposalKind::Standard, Perbill::from_percent(50), Perbill::from_percent(0)));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 3));
+		assert_eq!(MolochV2::abstain_votes(dao_id, 0), 6);
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(Some(FailureReason::NotEnoughYesVotes)));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		assert_eq!(MolochV2::abstain_votes(dao_id, 0), 0);
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(None));
+
+		// votes are final once the voting period is over
+		System::set_block_number(41);
+		assert_noop!(MolochV2::retract_vote(Origin::signed(1), dao_id, 0), Error::<Test>::ProposalExpired);
+	});
+}
+
+#[test]
+fn quadratic_votes_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 9, 0), (2, 4, 0), (3, 4, 0)], vec![DORA], 0, 10, 2, 2, 1, 100, 50));
+		let dao_id = MolochV2::dao_count() - 1;
+		assert_eq!(MolochV2::voting_mode(dao_id), VotingMode::Linear);
+		assert_noop!(MolochV2::set_voting_mode(Origin::signed(1), dao_id, VotingMode::Quadratic), DispatchError::BadOrigin);
+		assert_noop!(MolochV2::set_voting_mode(Origin::root(), dao_id + 1, VotingMode::Quadratic), Error::<Test>::DaoNotExist);
+
+		// the mode is fixed when a proposal is sponsored
+		for (index, mode) in [VotingMode::Quadratic, VotingMode::Linear].iter().enumerate() {
+			assert_ok!(MolochV2::set_voting_mode(Origin::root(), dao_id, *mode));
+			assert_eq!(last_event(), RawEvent::VotingModeSet(dao_id, *mode));
+			let applicant = index as u64 + 4;
+			assert_ok!(MolochV2::submit_proposal(Origin::signed(applicant), dao_id, applicant, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+			assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, index as u128));
+		}
+		assert_eq!(MolochV2::proposals(dao_id, 0).voting_mode, VotingMode::Quadratic);
+		assert_eq!(MolochV2::proposals(dao_id, 1).voting_mode, VotingMode::Linear);
+		System::set_block_number(21);
+
+		for index in 0..2 {
+			assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, index, 1));
+			assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, index, 2));
+			assert_ok!(MolochV2::submit_vote(Origin::signed(3), dao_id, index, 2));
+		}
+		// 3 against 2 + 2 votes, although 9 against 8 shares
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!((proposal.yes_votes, proposal.no_votes), (3, 4));
+		assert_eq!((proposal.yes_shares, proposal.no_shares), (9, 8));
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(Some(FailureReason::NotEnoughYesVotes)));
+		let proposal = MolochV2::proposals(dao_id, 1);
+		assert_eq!((proposal.yes_votes, proposal.no_votes), (9, 8));
+		assert_eq!(MolochV2::preview_outcome(dao_id, 1), Ok(None));
+
+		// a retracted vote takes back its weight and its shares
+		assert_ok!(MolochV2::retract_vote(Origin::signed(3), dao_id, 0));
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!((proposal.no_votes, proposal.no_shares), (2, 4));
+		assert_eq!(MolochV2::preview_outcome(dao_id, 0), Ok(None));
+	});
+}
+
+#[test]
+fn migrate_voting_mode_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		// proposal stored before the voting mode
+		let voted = ProposalV2::<u64, CurrencyId> {
+			proposer: 2,
+			applicant: 2,
+			sponsor: 1,
+			status: ProposalStatus::Sponsored,
+			starting_period: 1,
+			yes_votes: 7,
+			no_votes: 3,
+			max_total_shares_at_yes: 10,
+			..Default::default()
+		};
+		frame_support::storage::unhashed::put(&Proposals::<Test>::hashed_key_for(dao_id, 0), &voted);
+		StorageVersion::put(Releases::V5_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!(proposal.voting_mode, VotingMode::Linear);
+		assert_eq!((proposal.yes_votes, proposal.no_votes), (7, 3));
+		assert_eq!((proposal.yes_shares, proposal.no_shares), (7, 3));
+		assert_eq!((proposal.voting_period_length, proposal.grace_period_length), (2, 2));
+		assert_eq!(proposal.max_total_shares_at_yes, 10);
+		assert_eq!(proposal.status, ProposalStatus::Sponsored);
+	});
+}
+
+#[test]
+fn migrate_proposal_lengths_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		// proposal stored before the lengths
+		let sponsored = ProposalV6::<u64, CurrencyId> {
+			proposer: 2,
+			applicant: 2,
+			sponsor: 1,
+			status: ProposalStatus::Sponsored,
+			starting_period: 1,
+			yes_votes: 7,
+			yes_shares: 7,
+			..Default::default()
+		};
+		frame_support::storage::unhashed::put(&Proposals::<Test>::hashed_key_for(dao_id, 0), &sponsored);
+		StorageVersion::put(Releases::V7_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!((proposal.voting_period_length, proposal.grace_period_length), (2, 2));
+		assert_eq!((proposal.starting_period, proposal.yes_votes, proposal.yes_shares), (1, 7, 7));
+		assert_eq!(proposal.status, ProposalStatus::Sponsored);
+	});
+}
+
+#[test]
+fn migrate_stream_commitments_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		// streams started before the payments were committed
+		let stream = PayoutStream { recipient: 2, token: USDT, start: 1, periods: 10, per_period: 50, claimed: 100 };
+		PayoutStreams::<Test>::insert(dao_id, 0, stream.clone());
+		PayoutStreams::<Test>::insert(dao_id, 3, PayoutStream { claimed: 0, ..stream });
+		StorageVersion::put(Releases::V6_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 900);
+		assert_eq!(MolochV2::stream_commitment(dao_id, DORA), 0);
+	});
+}
+
+#[test]
+fn migrate_escrow_rewards_works() {
+	new_test_ext().execute_with(|| {
+		let dao_id = summon_with(1);
+		// escrows stored before the processing reward, a pending one and a sponsored one
+		let pending = EscrowV1::<u64, CurrencyId> { tribute_payer: 2, tribute_token: DORA, tribute: 50, deposit_payer: 2, deposit_token: DORA, deposit: 0 };
+		let sponsored = EscrowV1::<u64, CurrencyId> { deposit_payer: 1, deposit: 100, ..pending.clone() };
+		frame_support::storage::unhashed::put(&crate::ProposalEscrows::<Test>::hashed_key_for(dao_id, 0), &pending);
+		frame_support::storage::unhashed::put(&crate::ProposalEscrows::<Test>::hashed_key_for(dao_id, 1), &sponsored);
+		StorageVersion::put(Releases::V8_0_0);
+
+		MolochV2::on_runtime_upgrade();
+		assert_eq!(MolochV2::storage_version(), Releases::V9_0_0);
+		assert_eq!(MolochV2::proposal_escrow(dao_id, 0).processing_reward, 0);
+		let escrow = MolochV2::proposal_escrow(dao_id, 1);
+		assert_eq!((escrow.tribute, escrow.deposit_payer, escrow.deposit, escrow.processing_reward), (50, 1, 100, 50));
+	});
+}
+
+#[test]
+fn conviction_votes_lock_shares() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+
+		assert_noop!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 0, 3, Conviction::Locked2x), Error::<Test>::InvalidVote);
+		assert_ok!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 0, 1, Conviction::Locked3x));
+		assert_eq!(MolochV2::proposals(dao_id, 0).yes_votes, 12);
+		// locked for 2 voting periods after the grace period
+		assert_eq!(MolochV2::share_locks(dao_id, 2), vec![ShareLock { proposal_id: 0, shares: 4, until: 9 }]);
+		assert_noop!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 0, 1, Conviction::Locked3x), Error::<Test>::MemberHasVoted);
+		// a lower conviction replaces the lock
+		assert_ok!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 0, 1, Conviction::Locked2x));
+		let proposal = MolochV2::proposals(dao_id, 0);
+		assert_eq!((proposal.yes_votes, proposal.yes_shares), (8, 4));
+		assert_eq!(MolochV2::share_locks(dao_id, 2), vec![ShareLock { proposal_id: 0, shares: 4, until: 7 }]);
+
+		// a retracted vote releases its lock
+		assert_ok!(MolochV2::submit_conviction_vote(Origin::signed(1), dao_id, 1, 2, Conviction::Locked6x));
+		assert_eq!(MolochV2::locked_shares(dao_id, &1), 6);
+		assert_ok!(MolochV2::retract_vote(Origin::signed(1), dao_id, 1));
+		assert_eq!(MolochV2::share_locks(dao_id, 1), vec![]);
+		assert_eq!(MolochV2::vote_conviction((dao_id, 1), 1), Conviction::None);
+
+		// 8 against 6 votes, although 4 against 6 shares
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 2));
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+		assert!(MolochV2::proposals(dao_id, 0).did_pass());
+
+		// the locked shares can not be ragequit until the lock is over
+		assert_noop!(MolochV2::rage_quit(Origin::signed(2), dao_id, 1, 0), Error::<Test>::SharesLocked);
+		System::set_block_number(71);
+		assert_eq!(MolochV2::locked_shares(dao_id, &2), 0);
+		assert_ok!(MolochV2::rage_quit(Origin::signed(2), dao_id, 1, 0));
+		assert_eq!(MolochV2::members(dao_id, 2).shares, 3);
+	});
+}
+
+#[test]
+fn changed_yes_vote_keeps_ragequit_blocked() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+		for index in 0..2 {
+			assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, index, 1));
+		}
+		System::set_block_number(61);
+		for index in 0..2 {
+			assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, index));
+		}
+		// voting in [7, 9)
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(5), dao_id, 5, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 2));
+		System::set_block_number(71);
+
+		// a conviction yes vote changed to a plain yes vote still holds back the ragequit
+		assert_ok!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 2, 1, Conviction::Locked2x));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(2), dao_id, 2, 1));
+		assert_eq!(MolochV2::members(dao_id, 2).highest_index_yes_vote, 2);
+		assert_noop!(MolochV2::rage_quit(Origin::signed(2), dao_id, 1, 0), Error::<Test>::ProposalNotProcessed);
+
+		System::set_block_number(111);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 2));
+		assert_ok!(MolochV2::rage_quit(Origin::signed(2), dao_id, 1, 0));
+		assert_eq!(MolochV2::members(dao_id, 2).shares, 3);
+	});
+}
+
+#[test]
+fn guild_kick_releases_share_locks() {
+	new_test_ext().execute_with(|| {
+		let dao_id = two_proposals_voting();
+		// the shares of member 2 are locked until period 15
+		assert_ok!(MolochV2::submit_conviction_vote(Origin::signed(2), dao_id, 0, 1, Conviction::Locked6x));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		System::set_block_number(61);
+		for index in 0..2 {
+			assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, index));
+		}
+		// voting in [7, 9)
+		assert_ok!(MolochV2::submit_guild_kick_proposal(Origin::signed(1), dao_id, 2, b"kick".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 2));
+		System::set_block_number(71);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 2, 1));
+		System::set_block_number(111);
+		assert_eq!(MolochV2::locked_shares(dao_id, &2), 4);
+		assert_ok!(MolochV2::process_guild_kick_proposal(Origin::signed(1), dao_id, 2));
+
+		// the kicked shares are loot, which can be ragekicked while the lock would still hold
+		let member = MolochV2::members(dao_id, 2);
+		assert_eq!((member.shares, member.loot), (0, 4));
+		assert_eq!(MolochV2::share_locks(dao_id, 2), vec![]);
+		assert_ok!(MolochV2::rage_kick(Origin::signed(0), dao_id, 2));
+		assert_eq!(MolochV2::members(dao_id, 2).loot, 0);
+	});
+}
+
+fn signed_vote(signer: u64, dao_id: DaoId, delegate_key: u64, proposal_index: u128, vote_unit: u8, nonce: u64) -> SignedVote<u64, TestSignature> {
+	let message = VoteMessage { dao_id, proposal_index, vote_unit, nonce };
+	SignedVote { delegate_key, proposal_index, vote_unit, nonce, signature: TestSignature(signer, message.encode()) }
+}
+
+#[test]
+fn relayed_signed_votes_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 6, 0), (2, 4, 0)], vec![DORA], 1000, 10, 2, 2, 1, 100, 50));
+		let dao_id = MolochV2::dao_count() - 1;
+		assert_noop!(MolochV2::set_relay_refund(Origin::signed(1), dao_id, 10), DispatchError::BadOrigin);
+		assert_ok!(MolochV2::set_relay_refund(Origin::root(), dao_id, 10));
+		assert_eq!(last_event(), RawEvent::RelayRefundSet(dao_id, 10));
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 0, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(21);
+
+		let relayer = 9;
+		let votes = vec![
+			signed_vote(1, dao_id, 1, 0, 1, 0),
+			// signed by another key
+			signed_vote(1, dao_id, 2, 0, 2, 0),
+			// not a member
+			signed_vote(5, dao_id, 5, 0, 1, 0),
+		];
+		assert_ok!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id, votes));
+		assert_eq!(last_event(), RawEvent::SignedVotesRelayed(dao_id, relayer, 1, 10));
+		let events = System::events();
+		assert!(events.iter().any(|r| r.event == Event::moloch_v2(RawEvent::SignedVoteFailed(dao_id, 2, 0, Error::<Test>::BadVoteSignature.into()))));
+		assert!(events.iter().any(|r| r.event == Event::moloch_v2(RawEvent::SignedVoteFailed(dao_id, 5, 0, Error::<Test>::NotMember.into()))));
+		assert_eq!(MolochV2::proposals(dao_id, 0).yes_votes, 6);
+		assert_eq!(MolochV2::member_vote(dao_id, 0, 1), Some(1));
+		assert_eq!(MolochV2::user_token_balances((dao_id, relayer), DORA), 10);
+		// the nonce of a bad signature is not used up, the nonce of a failed vote is
+		assert_eq!((MolochV2::vote_nonce(dao_id, 1), MolochV2::vote_nonce(dao_id, 2), MolochV2::vote_nonce(dao_id, 5)), (1, 0, 1));
+
+		// a relayed vote can not be replayed
+		assert_ok!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id, vec![
+			signed_vote(1, dao_id, 1, 0, 1, 0),
+			signed_vote(2, dao_id, 2, 0, 2, 0),
+		]));
+		assert!(System::events().iter().any(|r| r.event == Event::moloch_v2(RawEvent::SignedVoteFailed(dao_id, 1, 0, Error::<Test>::BadVoteNonce.into()))));
+		assert_eq!(last_event(), RawEvent::SignedVotesRelayed(dao_id, relayer, 1, 10));
+		assert_eq!(MolochV2::proposals(dao_id, 0).no_votes, 4);
+		assert_eq!(MolochV2::user_token_balances((dao_id, relayer), DORA), 20);
+		assert_noop!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id + 1, vec![]), Error::<Test>::DaoNotExist);
+	});
+}
+
+#[test]
+fn relay_refunds_are_paid_once_per_vote_from_the_free_guild_bank() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 6, 0), (2, 4, 0)], vec![DORA], 1000, 10, 2, 2, 1, 100, 50));
+		let dao_id = MolochV2::dao_count() - 1;
+		assert_ok!(MolochV2::set_relay_refund(Origin::root(), dao_id, 10));
+		// 900 of the guild bank are committed to a payout stream
+		let schedule = VestingSchedule { cliff: 0, periods: 10, per_period: 90 };
+		assert_ok!(MolochV2::submit_vesting_proposal(Origin::signed(2), dao_id, 2, 0, DORA, 0, 0, DORA, schedule, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+		assert_eq!(MolochV2::free_guild_bank_balance(dao_id, DORA), 100);
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 0, DORA, 1, 0, 0, DORA, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		System::set_block_number(61);
+
+		let relayer = 9;
+		assert_ok!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id, vec![signed_vote(1, dao_id, 1, 1, 1, 0)]));
+		assert_eq!(last_event(), RawEvent::SignedVotesRelayed(dao_id, relayer, 1, 10));
+		// flipping the vote is counted but not refunded again
+		assert_ok!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id, vec![
+			signed_vote(1, dao_id, 1, 1, 2, 1),
+			signed_vote(1, dao_id, 1, 1, 1, 2),
+		]));
+		assert_eq!(last_event(), RawEvent::SignedVotesRelayed(dao_id, relayer, 2, 0));
+		assert_eq!(MolochV2::proposals(dao_id, 1).yes_votes, 6);
+		assert_eq!(MolochV2::user_token_balances((dao_id, relayer), DORA), 10);
+
+		// the refund leaves the committed payments in the guild bank
+		assert_ok!(MolochV2::set_relay_refund(Origin::root(), dao_id, 1000));
+		assert_ok!(MolochV2::submit_votes_signed(Origin::signed(relayer), dao_id, vec![signed_vote(2, dao_id, 2, 1, 2, 0)]));
+		assert_eq!(last_event(), RawEvent::SignedVotesRelayed(dao_id, relayer, 1, 90));
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 900);
+		assert_eq!(MolochV2::stream_commitment(dao_id, DORA), 900);
+
+		System::set_block_number(201);
+		assert_ok!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), DORA), 900);
+	});
+}
+
+#[test]
+fn action_proposals_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		let filtered = Call::MolochV2(crate::Call::abort(dao_id, 0));
+		assert_noop!(MolochV2::submit_action_proposal(Origin::signed(2), dao_id, Box::new(filtered), 0, DORA, b"test_action".to_vec()), Error::<Test>::CallFiltered);
+		let remark = Box::new(Call::System(frame_system::Call::remark(vec![])));
+		assert_noop!(MolochV2::submit_action_proposal(Origin::signed(2), dao_id, remark, 0, BTC, vec![]), Error::<Test>::TokenNotWhitelisted);
+		assert_noop!(MolochV2::submit_action_proposal(Origin::signed(2), dao_id + 1, Box::new(Call::System(frame_system::Call::remark(vec![]))), 0, DORA, vec![]), Error::<Test>::DaoNotExist);
+
+		// the second transfer is more than the action account holds
+		for (index, amount) in [50, 1000].iter().enumerate() {
+			let transfer = Call::Balances(pallet_balances::Call::transfer(7, *amount));
+			assert_ok!(MolochV2::submit_action_proposal(Origin::signed(2), dao_id, Box::new(transfer.clone()), 0, DORA, b"test_action".to_vec()));
+			assert_eq!(MolochV2::proposal_action(dao_id, index as u128), Some(transfer));
+			assert_eq!(MolochV2::proposals(dao_id, index as u128).kind, ProposalKind::Action);
+			assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, index as u128));
+		}
+		System::set_block_number(21);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		assert_ok!(Balances::transfer(Origin::signed(5), MolochV2::action_account(dao_id), 100));
+
+		System::set_block_number(51);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(3), dao_id, 0), Error::<Test>::NotStandardProposal);
+		assert_ok!(MolochV2::process_action_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::ActionExecuted(dao_id, 0, Ok(())));
+		assert!(MolochV2::proposals(dao_id, 0).did_pass());
+		assert_eq!(MolochV2::proposal_action(dao_id, 0), None);
+		assert_eq!(Balances::free_balance(MolochV2::action_account(dao_id)), 50);
+		assert_eq!(Balances::free_balance(7), 50);
+
+		// a failing call does not hold up the queue
+		System::set_block_number(61);
+		assert_ok!(MolochV2::process_action_proposal(Origin::signed(3), dao_id, 1));
+		assert!(matches!(last_event(), RawEvent::ActionExecuted(_, 1, Err(_))));
+		assert!(MolochV2::proposals(dao_id, 1).did_pass());
+		assert_eq!(Balances::free_balance(7), 50);
+	});
+}
+
+#[test]
+fn action_proposals_are_funded_from_guild_bank() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(MolochV2::summon(Origin::signed(1), vec![(1, 1, 0)], vec![DORA], 1000, 10, 2, 2, 1, 100, 50));
+		let dao_id = 0;
+		let action_account = MolochV2::action_account(dao_id);
+
+		// the second payment is more than is left in the guild bank, the third call transfers more than its payment
+		for (index, (payment, amount)) in [(300, 300), (800, 800), (200, 500)].iter().enumerate() {
+			let transfer = Call::Balances(pallet_balances::Call::transfer(7, *amount));
+			assert_ok!(MolochV2::submit_action_proposal(Origin::signed(2), dao_id, Box::new(transfer), *payment, DORA, b"test_action".to_vec()));
+			assert_eq!(MolochV2::proposals(dao_id, index as u128).payment_requested, *payment as u128);
+			assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, index as u128));
+		}
+		System::set_block_number(21);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		System::set_block_number(31);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 2, 1));
+
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_action_proposal(Origin::signed(3), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::ActionExecuted(dao_id, 0, Ok(())));
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 700);
+		assert_eq!(MolochV2::user_token_balances((dao_id, action_account), DORA), 0);
+		assert_eq!(Balances::free_balance(action_account), 0);
+		assert_eq!(Balances::free_balance(7), 300);
+
+		System::set_block_number(61);
+		assert_ok!(MolochV2::process_action_proposal(Origin::signed(3), dao_id, 1));
+		assert_eq!(last_event(), RawEvent::ProcessActionProposal(dao_id, 1, false));
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 700);
+		assert_eq!(Balances::free_balance(7), 300);
+
+		// the payment of a failing call stays in the guild bank
+		System::set_block_number(71);
+		assert_ok!(MolochV2::process_action_proposal(Origin::signed(3), dao_id, 2));
+		assert!(matches!(last_event(), RawEvent::ActionExecuted(_, 2, Err(_))));
+		assert!(MolochV2::proposals(dao_id, 2).did_pass());
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, DORA), 700);
+		assert_eq!(MolochV2::user_token_balances((dao_id, action_account), DORA), 0);
+		assert_eq!(Balances::free_balance(action_account), 0);
+		assert_eq!(Balances::free_balance(7), 300);
+	});
+}
+
+#[test]
+fn vesting_payouts_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		let bad_schedule = VestingSchedule { cliff: 1, periods: 0, per_period: 50 };
+		assert_noop!(MolochV2::submit_vesting_proposal(Origin::signed(2), dao_id, 2, 1000, USDT, 1, 0, USDT, bad_schedule, vec![]), Error::<Test>::InvalidVestingSchedule);
+		assert_noop!(MolochV2::submit_cancel_stream_proposal(Origin::signed(1), dao_id, 0, vec![]), Error::<Test>::StreamNotExist);
+
+		// 500 USDT vest over 10 periods after a cliff of 1 period
+		let schedule = VestingSchedule { cliff: 1, periods: 10, per_period: 50 };
+		assert_ok!(MolochV2::submit_vesting_proposal(Origin::signed(2), dao_id, 2, 1000, USDT, 1, 0, USDT, schedule, b"test_vesting".to_vec()));
+		assert_eq!(MolochV2::proposals(dao_id, 0).payment_requested, 500);
+		assert_eq!(MolochV2::proposal_vesting(dao_id, 0), Some(schedule));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+
+		// the payment stays in the guild bank once the proposal passes
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::PayoutStreamStarted(dao_id, 0, 2, USDT, 6, 10, 50));
+		assert_eq!(MolochV2::proposal_vesting(dao_id, 0), None);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), 1000);
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 500);
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), USDT), 0);
+		assert_noop!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0), Error::<Test>::NothingToClaim);
+
+		// periods 6 and 7 have vested
+		System::set_block_number(71);
+		assert_noop!(MolochV2::claim_payout(Origin::signed(3), dao_id, 0), Error::<Test>::NotStreamRecipient);
+		assert_ok!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::PayoutClaimed(dao_id, 0, 2, 100));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), USDT), 100);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), 900);
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 400);
+
+		// the DAO cancels the stream, it stops vesting once the proposal is processed in period 12
+		assert_ok!(MolochV2::submit_cancel_stream_proposal(Origin::signed(1), dao_id, 0, b"test_cancel".to_vec()));
+		assert_eq!(MolochV2::proposals(dao_id, 1).kind, ProposalKind::CancelStream);
+		assert_eq!(MolochV2::proposal_stream_cancel(dao_id, 1), Some(0));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		System::set_block_number(81);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		System::set_block_number(121);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(1), dao_id, 1), Error::<Test>::NotStandardProposal);
+		assert_ok!(MolochV2::process_cancel_stream_proposal(Origin::signed(1), dao_id, 1));
+		assert_eq!(last_event(), RawEvent::ProcessCancelStreamProposal(dao_id, 1, 0, true));
+		assert_eq!(MolochV2::payout_stream(dao_id, 0).map(|stream| stream.periods), Some(7));
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 250);
+
+		// only what vested before the cancellation can be claimed, the rest stays in the guild bank
+		System::set_block_number(201);
+		assert_ok!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::PayoutClaimed(dao_id, 0, 2, 250));
+		assert_eq!(MolochV2::payout_stream(dao_id, 0), None);
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), USDT), 350);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), 650);
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 0);
+		assert_noop!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0), Error::<Test>::StreamNotExist);
+	});
+}
+
+#[test]
+fn payout_streams_are_committed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		let schedule = VestingSchedule { cliff: 0, periods: 10, per_period: 50 };
+		assert_ok!(MolochV2::submit_vesting_proposal(Origin::signed(2), dao_id, 2, 1000, USDT, 1, 0, USDT, schedule, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+		assert_eq!(MolochV2::free_guild_bank_balance(dao_id, USDT), 500);
+
+		// half of the shares quit with half of what is not committed to the stream
+		assert_ok!(MolochV2::rage_quit(Origin::signed(2), dao_id, 1, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), USDT), 250);
+		assert_eq!(MolochV2::guild_bank_balance(dao_id, USDT), 750);
+		assert_eq!(MolochV2::free_guild_bank_balance(dao_id, USDT), 250);
+
+		// a payment can't take from the stream either
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 0, USDT, 0, 0, 300, USDT, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		System::set_block_number(61);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		System::set_block_number(101);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 1));
+		assert!(System::events().into_iter().any(|r| r.event == Event::moloch_v2(RawEvent::ProposalFailed(dao_id, 1, FailureReason::PaymentExceedsGuildBank))));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 3), USDT), 0);
+
+		// the stream is still paid in full
+		System::set_block_number(201);
+		assert_ok!(MolochV2::claim_payout(Origin::signed(2), dao_id, 0));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 2), USDT), 750);
+		assert_eq!(MolochV2::stream_commitment(dao_id, USDT), 0);
+	});
+}
+
+#[test]
+fn cancel_stream_of_jailed_recipient_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		let schedule = VestingSchedule { cliff: 0, periods: 20, per_period: 25 };
+		assert_ok!(MolochV2::submit_vesting_proposal(Origin::signed(2), dao_id, 2, 1000, USDT, 1, 0, USDT, schedule, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+
+		// the recipient is kicked
+		assert_ok!(MolochV2::submit_guild_kick_proposal(Origin::signed(1), dao_id, 2, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		System::set_block_number(61);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+		System::set_block_number(101);
+		assert_ok!(MolochV2::process_guild_kick_proposal(Origin::signed(1), dao_id, 1));
+		assert!(MolochV2::members(dao_id, 2).jailed_at > 0);
+
+		// its stream can still be cancelled
+		assert_ok!(MolochV2::submit_cancel_stream_proposal(Origin::signed(1), dao_id, 0, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 2));
+		System::set_block_number(111);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 2, 1));
+		System::set_block_number(151);
+		assert_ok!(MolochV2::process_cancel_stream_proposal(Origin::signed(1), dao_id, 2));
+		assert_eq!(last_event(), RawEvent::ProcessCancelStreamProposal(dao_id, 2, 0, true));
+		assert_eq!(MolochV2::payout_stream(dao_id, 0).map(|stream| stream.periods), Some(11));
+	});
+}
+
+#[test]
+fn parameter_proposals_work() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		assert_noop!(MolochV2::submit_parameter_proposal(Origin::signed(2), dao_id, ParameterChange::default(), vec![]), Error::<Test>::NoParameterChange);
+		let too_long = ParameterChange { voting_period_length: Some(MaxVotingPeriodLength::get() + 1), ..Default::default() };
+		assert_noop!(MolochV2::submit_parameter_proposal(Origin::signed(2), dao_id, too_long, vec![]), Error::<Test>::VotingPeriodLengthTooBig);
+		let reward_over_deposit = ParameterChange { processing_reward: Some(200), ..Default::default() };
+		assert_noop!(MolochV2::submit_parameter_proposal(Origin::signed(2), dao_id, reward_over_deposit, vec![]), Error::<Test>::NoEnoughProposalDeposit);
+
+		// longer periods, a longer voting window and a higher processing reward
+		let change = ParameterChange { period_duration: Some(20), voting_period_length: Some(3), proposal_deposit: Some(300), processing_reward: Some(80), ..Default::default() };
+		assert_ok!(MolochV2::submit_parameter_proposal(Origin::signed(2), dao_id, change.clone(), b"test_parameters".to_vec()));
+		assert_eq!(MolochV2::proposals(dao_id, 0).kind, ProposalKind::ParameterChange);
+		assert_eq!(MolochV2::proposal_parameter_change(dao_id, 0), Some(change));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		// queued behind it, starting in period 2
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 100, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		assert_eq!(MolochV2::proposal_escrow(dao_id, 1).processing_reward, 50);
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+
+		System::set_block_number(51);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0), Error::<Test>::NotStandardProposal);
+		assert_ok!(MolochV2::process_parameter_proposal(Origin::signed(1), dao_id, 0));
+		assert_eq!(last_event(), RawEvent::ProcessParameterProposal(dao_id, 0, true));
+		assert_eq!(MolochV2::proposal_parameter_change(dao_id, 0), None);
+		assert_eq!(MolochV2::period_duration(dao_id), 20);
+		assert_eq!(MolochV2::voting_period_length(dao_id), 3);
+		assert_eq!(MolochV2::grace_period_length(dao_id), 2);
+		assert_eq!(MolochV2::proposal_deposit(dao_id), 300);
+		assert_eq!(MolochV2::processing_reward(dao_id), 80);
+
+		// the current period keeps its number and the next ones last 20 blocks
+		assert_eq!(MolochV2::get_current_period(dao_id), 5);
+		assert_eq!(MolochV2::period_anchor(dao_id), Some((51, 5)));
+		System::set_block_number(70);
+		assert_eq!(MolochV2::get_current_period(dao_id), 5);
+		// the queued proposal keeps voting 2 periods and waiting 2, it is ready in period 6
+		assert_eq!(MolochV2::proposals(dao_id, 1).voting_period_length, 2);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(1), dao_id, 1), Error::<Test>::ProposalNotReady);
+		System::set_block_number(71);
+		assert_eq!(MolochV2::get_current_period(dao_id), 6);
+		// paid the reward it was sponsored with
+		assert_ok!(MolochV2::process_proposal(Origin::signed(3), dao_id, 1));
+		assert_eq!(MolochV2::user_token_balances((dao_id, 3), DORA), 50);
+	});
+}
+
+#[test]
+fn grace_reduction_keeps_proposals_in_grace() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		let change = ParameterChange { grace_period_length: Some(1), ..Default::default() };
+		assert_ok!(MolochV2::submit_parameter_proposal(Origin::signed(2), dao_id, change, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 100, DORA, 1, 0, 0, DORA, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 1));
+		System::set_block_number(21);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 1, 1));
+
+		// the grace period is cut while the second proposal is in it
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_parameter_proposal(Origin::signed(1), dao_id, 0));
+		assert_eq!(MolochV2::grace_period_length(dao_id), 1);
+		assert_eq!(MolochV2::proposal_status(dao_id, 1), ProposalStatus::Grace);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(1), dao_id, 1), Error::<Test>::ProposalNotReady);
+		System::set_block_number(61);
+		assert_eq!(MolochV2::proposal_status(dao_id, 1), ProposalStatus::ReadyToProcess);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 1));
+
+		// proposals sponsored since wait with the new length
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(3), dao_id, 3, 100, DORA, 1, 0, 0, DORA, vec![]));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 2));
+		let proposal = MolochV2::proposals(dao_id, 2);
+		assert_eq!((proposal.starting_period, proposal.voting_period_length, proposal.grace_period_length), (7, 2, 1));
+		assert_eq!(proposal.grace_ends(), 10);
+	});
+}
+
+#[test]
+fn pause_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		assert_noop!(MolochV2::pause(Origin::signed(1), CallCategory::All, None), DispatchError::BadOrigin);
+
+		// votes are paused until block 15
+		assert_ok!(MolochV2::pause(Origin::root(), CallCategory::Votes, Some(15)));
+		assert_eq!(last_event(), RawEvent::CallsPaused(CallCategory::Votes, Some(15)));
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 100, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_noop!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1), Error::<Test>::CallsArePaused);
+		System::set_block_number(15);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+
+		// pausing all calls stops the auto-processor as well
+		assert_ok!(MolochV2::pause(Origin::root(), CallCategory::All, None));
+		assert_noop!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 100, DORA, 1, 0, 0, DORA, vec![]), Error::<Test>::CallsArePaused);
+		System::set_block_number(51);
+		set_auto_process(u64::max_value(), None);
+		MolochV2::on_initialize(51);
+		assert_eq!(MolochV2::proposals(dao_id, 0).status, ProposalStatus::Sponsored);
+		assert_noop!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0), Error::<Test>::CallsArePaused);
+		assert_ok!(MolochV2::unpause(Origin::root(), CallCategory::All));
+		assert_eq!(last_event(), RawEvent::CallsUnpaused(CallCategory::All));
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+
+		// members can ragequit and withdraw while all calls are paused
+		assert_ok!(MolochV2::pause(Origin::root(), CallCategory::All, None));
+		assert_noop!(MolochV2::claim_payout(Origin::signed(1), dao_id, 0), Error::<Test>::CallsArePaused);
+		assert_ok!(MolochV2::rage_quit(Origin::signed(1), dao_id, 1, 0));
+		assert_eq!(MolochV2::members(dao_id, 1).shares, 0);
+		assert_ok!(MolochV2::withdraw_balance(Origin::signed(1), dao_id, DORA, 50));
+		assert_ok!(MolochV2::withdraw_balances(Origin::signed(1), dao_id, vec![DORA], vec![0], true));
+	});
+}
+
+#[test]
+fn failed_transfers_roll_back() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let dao_id = summon_with(1);
+		assert_ok!(MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 100, DORA, 1, 0, 0, DORA, b"test_proposal".to_vec()));
+		assert_noop!(
+			MolochV2::submit_proposal(Origin::signed(2), dao_id, 2, 5000, DORA, 1, 0, 0, DORA, vec![]),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+
+		// the sponsor can not pay the deposit
+		assert_ok!(Balances::transfer(Origin::signed(1), 5, Balances::free_balance(1) - 50));
+		assert_noop!(
+			MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_ok!(Balances::transfer(Origin::signed(5), 1, 1000));
+		assert_ok!(MolochV2::sponsor_proposal(Origin::signed(1), dao_id, 0));
+		System::set_block_number(11);
+		assert_ok!(MolochV2::submit_vote(Origin::signed(1), dao_id, 0, 1));
+		System::set_block_number(51);
+		assert_ok!(MolochV2::process_proposal(Origin::signed(1), dao_id, 0));
+		// the processing reward and the rest of the deposit
+		assert_eq!(MolochV2::user_token_balances((dao_id, 1), DORA), 100);
+
+		// the first withdrawal is rolled back when the second one fails
+		let free_balance = Balances::free_balance(1);
+		assert_noop!(
+			MolochV2::withdraw_balances(Origin::signed(1), dao_id, vec![DORA, USDT], vec![100, 10], false),
+			Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(MolochV2::user_token_balances((dao_id, 1), DORA), 100);
+		assert_eq!(Balances::free_balance(1), free_balance);
+		assert_ok!(MolochV2::withdraw_balances(Origin::signed(1), dao_id, vec![DORA, USDT], vec![100, 0], false));
+		assert_eq!(Balances::free_balance(1), free_balance + 100);
+	});
+}
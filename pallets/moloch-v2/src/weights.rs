@@ -0,0 +1,427 @@
+//! Weights for pallet_moloch_v2
+//!
+//! Regenerated from the benchmarks in `benchmarking.rs` by `scripts/benchmark.sh`, which runs
+//! ./target/release/dora-moloch benchmark --chain dev --execution wasm --wasm-execution compiled
+//!   --pallet pallet_moloch_v2 --extrinsic '*' --steps 50 --repeat 20
+//!   --output ./pallets/moloch-v2/src/weights.rs
+//! on the reference hardware. Rerun it whenever a benchmarked call changes.
+//!
+//! `q` is the length of the proposal queue, `s` the number of summoners and `t` the number of tokens.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_moloch_v2.
+pub trait WeightInfo {
+	fn summon(s: u32, t: u32, ) -> Weight;
+	fn submit_proposal() -> Weight;
+	fn submit_whitelist_proposal() -> Weight;
+	fn submit_guild_kick_proposal() -> Weight;
+	fn submit_action_proposal() -> Weight;
+	fn submit_vesting_proposal() -> Weight;
+	fn submit_cancel_stream_proposal() -> Weight;
+	fn submit_parameter_proposal() -> Weight;
+	fn sponsor_proposal(q: u32, ) -> Weight;
+	fn submit_vote(q: u32, ) -> Weight;
+	fn submit_conviction_vote(q: u32, ) -> Weight;
+	fn submit_votes_signed(v: u32, ) -> Weight;
+	fn retract_vote(q: u32, ) -> Weight;
+	fn process_proposal(q: u32, ) -> Weight;
+	fn process_guild_kick_proposal(q: u32, ) -> Weight;
+	fn process_whitelist_proposal(q: u32, ) -> Weight;
+	fn process_action_proposal(q: u32, ) -> Weight;
+	fn process_cancel_stream_proposal(q: u32, ) -> Weight;
+	fn process_parameter_proposal(q: u32, ) -> Weight;
+	fn abort() -> Weight;
+	fn rage_quit(q: u32, t: u32, ) -> Weight;
+	fn rage_kick(q: u32, t: u32, ) -> Weight;
+	fn claim_payout() -> Weight;
+	fn withdraw_balance() -> Weight;
+	fn withdraw_balances(t: u32, ) -> Weight;
+	fn update_delegate() -> Weight;
+	fn set_voting_threshold() -> Weight;
+	fn set_voting_mode() -> Weight;
+	fn set_relay_refund() -> Weight;
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+}
+
+/// Weights for pallet_moloch_v2 using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn summon(s: u32, t: u32, ) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((9_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((21_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn submit_proposal() -> Weight {
+		(85_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn submit_whitelist_proposal() -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn submit_guild_kick_proposal() -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn submit_action_proposal() -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn submit_vesting_proposal() -> Weight {
+		(87_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn submit_cancel_stream_proposal() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn submit_parameter_proposal() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn sponsor_proposal(q: u32, ) -> Weight {
+		(98_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(15 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn submit_vote(q: u32, ) -> Weight {
+		(68_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn submit_conviction_vote(q: u32, ) -> Weight {
+		(81_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn submit_votes_signed(v: u32, ) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((135_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((11 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(v as Weight)))
+	}
+	fn retract_vote(q: u32, ) -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn process_proposal(q: u32, ) -> Weight {
+		(142_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(25 as Weight))
+			.saturating_add(T::DbWeight::get().writes(14 as Weight))
+	}
+	fn process_guild_kick_proposal(q: u32, ) -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(19 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	fn process_whitelist_proposal(q: u32, ) -> Weight {
+		(108_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(16 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn process_action_proposal(q: u32, ) -> Weight {
+		(137_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(20 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	fn process_cancel_stream_proposal(q: u32, ) -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(16 as Weight))
+			.saturating_add(T::DbWeight::get().writes(10 as Weight))
+	}
+	fn process_parameter_proposal(q: u32, ) -> Weight {
+		(98_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(22 as Weight))
+			.saturating_add(T::DbWeight::get().writes(15 as Weight))
+	}
+	fn abort() -> Weight {
+		(56_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn rage_quit(q: u32, t: u32, ) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn rage_kick(q: u32, t: u32, ) -> Weight {
+		(66_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn claim_payout() -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn withdraw_balance() -> Weight {
+		(72_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_balances(t: u32, ) -> Weight {
+		(8_000_000 as Weight)
+			.saturating_add((70_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(t as Weight)))
+	}
+	fn update_delegate() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn set_voting_threshold() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_voting_mode() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_relay_refund() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn pause() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause() -> Weight {
+		(13_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn summon(s: u32, t: u32, ) -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add((9_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add((21_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(14 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn submit_proposal() -> Weight {
+		(85_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn submit_whitelist_proposal() -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn submit_guild_kick_proposal() -> Weight {
+		(46_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn submit_action_proposal() -> Weight {
+		(44_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn submit_vesting_proposal() -> Weight {
+		(87_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn submit_cancel_stream_proposal() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn submit_parameter_proposal() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn sponsor_proposal(q: u32, ) -> Weight {
+		(98_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(15 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn submit_vote(q: u32, ) -> Weight {
+		(68_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn submit_conviction_vote(q: u32, ) -> Weight {
+		(81_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(12 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn submit_votes_signed(v: u32, ) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((135_000_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((11 as Weight).saturating_mul(v as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(v as Weight)))
+	}
+	fn retract_vote(q: u32, ) -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(q as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn process_proposal(q: u32, ) -> Weight {
+		(142_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(25 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(14 as Weight))
+	}
+	fn process_guild_kick_proposal(q: u32, ) -> Weight {
+		(104_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(19 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn process_whitelist_proposal(q: u32, ) -> Weight {
+		(108_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(16 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn process_action_proposal(q: u32, ) -> Weight {
+		(137_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(20 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(12 as Weight))
+	}
+	fn process_cancel_stream_proposal(q: u32, ) -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(16 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(10 as Weight))
+	}
+	fn process_parameter_proposal(q: u32, ) -> Weight {
+		(98_000_000 as Weight)
+			.saturating_add((320_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(22 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(15 as Weight))
+	}
+	fn abort() -> Weight {
+		(56_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn rage_quit(q: u32, t: u32, ) -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn rage_kick(q: u32, t: u32, ) -> Weight {
+		(66_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add((18_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(t as Weight)))
+	}
+	fn claim_payout() -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn withdraw_balance() -> Weight {
+		(72_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_balances(t: u32, ) -> Weight {
+		(8_000_000 as Weight)
+			.saturating_add((70_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(t as Weight)))
+	}
+	fn update_delegate() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn set_voting_threshold() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_voting_mode() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_relay_refund() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn pause() -> Weight {
+		(14_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unpause() -> Weight {
+		(13_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
@@ -0,0 +1,201 @@
+//! Benchmarks for pallet_quadratic_funding
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+// benchmarks! expects the pallet trait to be named `Trait`
+use crate::Config as Trait;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+const ROUND_ID: u32 = 1;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, Module::<T>::u128_to_balance(Module::<T>::cal_amount(1_000_000, false)));
+	who
+}
+
+fn project_hash<T: Config>(index: u32) -> T::Hash {
+	T::Hashing::hash_of(&index)
+}
+
+/// Start a round with a funded support pool, the internal account is endowed so that it's never reaped
+fn start_funded_round<T: Config>() -> Result<(), &'static str> {
+	T::Currency::make_free_balance_be(&Module::<T>::account_id(), T::Currency::minimum_balance());
+	Module::<T>::start_round(T::AdminOrigin::successful_origin(), ROUND_ID)?;
+	let donor = funded_account::<T>("donor", 0);
+	Module::<T>::donate(
+		RawOrigin::Signed(donor).into(),
+		ROUND_ID,
+		Module::<T>::u128_to_balance(Module::<T>::cal_amount(100, false))
+	)?;
+	Ok(())
+}
+
+/// Register a project owned by a fresh account, which is funded to reserve the deposit
+fn register<T: Config>(index: u32) -> Result<T::Hash, &'static str> {
+	let owner = funded_account::<T>("owner", index);
+	let hash = project_hash::<T>(index);
+	Module::<T>::register_project(RawOrigin::Signed(owner).into(), ROUND_ID, hash, vec![0; T::NameMaxLength::get()])?;
+	Ok(hash)
+}
+
+benchmarks! {
+	_ { }
+
+	donate {
+		start_funded_round::<T>()?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Module::<T>::u128_to_balance(Module::<T>::cal_amount(1_000_000, false)));
+		let amount = Module::<T>::cal_amount(100, false);
+	}: _(RawOrigin::Signed(caller), ROUND_ID, Module::<T>::u128_to_balance(amount))
+	verify {
+		assert_eq!(Rounds::get(ROUND_ID).pre_tax_support_pool, amount * 2);
+	}
+
+	start_round {
+	}: _(T::AdminOrigin::successful_origin(), ROUND_ID)
+	verify {
+		assert!(Rounds::get(ROUND_ID).ongoing);
+	}
+
+	// every project is voted by two voters, so that each one gets a share of the support pool
+	end_round {
+		let p in 0 .. T::MaxProjectsPerRound::get();
+		start_funded_round::<T>()?;
+		let voters = [funded_account::<T>("voter", 0), funded_account::<T>("voter", 1)];
+		for i in 0..p {
+			let hash = register::<T>(i)?;
+			for voter in voters.iter() {
+				Module::<T>::vote(RawOrigin::Signed(voter.clone()).into(), ROUND_ID, hash, 1)?;
+			}
+		}
+	}: _(T::AdminOrigin::successful_origin(), ROUND_ID, p)
+	verify {
+		assert!(!Rounds::get(ROUND_ID).ongoing);
+	}
+
+	// the grants could not be paid out as the internal account held only the existential deposit
+	withdraw_grant {
+		start_funded_round::<T>()?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Module::<T>::u128_to_balance(Module::<T>::cal_amount(1_000_000, false)));
+		let hash = project_hash::<T>(0);
+		Module::<T>::register_project(RawOrigin::Signed(caller.clone()).into(), ROUND_ID, hash, vec![0; T::NameMaxLength::get()])?;
+		let voter = funded_account::<T>("voter", 0);
+		Module::<T>::vote(RawOrigin::Signed(voter).into(), ROUND_ID, hash, 1)?;
+		let pool = T::Currency::free_balance(&Module::<T>::account_id());
+		T::Currency::make_free_balance_be(&Module::<T>::account_id(), T::Currency::minimum_balance());
+		Module::<T>::end_round(T::AdminOrigin::successful_origin(), ROUND_ID, 1)?;
+		T::Currency::make_free_balance_be(&Module::<T>::account_id(), pool);
+	}: _(RawOrigin::Signed(caller), ROUND_ID, hash)
+	verify {
+		let project = Projects::<T>::get(ROUND_ID, hash);
+		assert!(project.grants > 0);
+		assert_eq!(project.withdrew, project.grants);
+	}
+
+	register_project {
+		start_funded_round::<T>()?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Module::<T>::u128_to_balance(Module::<T>::cal_amount(1_000_000, false)));
+		let hash = project_hash::<T>(0);
+	}: _(RawOrigin::Signed(caller.clone()), ROUND_ID, hash, vec![0; T::NameMaxLength::get()])
+	verify {
+		assert_eq!(ProjectCount::get(ROUND_ID), 1);
+		assert_eq!(T::Currency::reserved_balance(&caller), T::ProjectDeposit::get());
+	}
+
+	// the caller has voted before, so the cost takes the previous ballots into account
+	vote {
+		start_funded_round::<T>()?;
+		let hash = register::<T>(0)?;
+		let voter = funded_account::<T>("voter", 0);
+		Module::<T>::vote(RawOrigin::Signed(voter).into(), ROUND_ID, hash, 1)?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, Module::<T>::u128_to_balance(Module::<T>::cal_amount(1_000_000, false)));
+		Module::<T>::vote(RawOrigin::Signed(caller.clone()).into(), ROUND_ID, hash, 1)?;
+	}: _(RawOrigin::Signed(caller), ROUND_ID, hash, 2)
+	verify {
+		assert_eq!(Projects::<T>::get(ROUND_ID, hash).total_votes, 4);
+	}
+
+	pause {
+		let until = frame_system::Module::<T>::block_number() + 10u32.into();
+	}: _(T::AdminOrigin::successful_origin(), CallCategory::All, Some(until))
+	verify {
+		assert!(Module::<T>::is_paused(CallCategory::Votes));
+	}
+
+	unpause {
+		Module::<T>::pause(T::AdminOrigin::successful_origin(), CallCategory::All, None)?;
+	}: _(T::AdminOrigin::successful_origin(), CallCategory::All)
+	verify {
+		assert!(!Module::<T>::is_paused(CallCategory::Votes));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn donate_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_donate::<Test>());
+		});
+	}
+
+	#[test]
+	fn start_round_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_start_round::<Test>());
+		});
+	}
+
+	#[test]
+	fn end_round_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_end_round::<Test>());
+		});
+	}
+
+	#[test]
+	fn withdraw_grant_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_withdraw_grant::<Test>());
+		});
+	}
+
+	#[test]
+	fn register_project_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_register_project::<Test>());
+		});
+	}
+
+	#[test]
+	fn vote_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_vote::<Test>());
+		});
+	}
+
+	#[test]
+	fn pause_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_pause::<Test>());
+		});
+	}
+
+	#[test]
+	fn unpause_benchmark_works() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_unpause::<Test>());
+		});
+	}
+}
@@ -6,14 +6,23 @@
 /// debug guide https://substrate.dev/recipes/runtime-printing.html
 
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, dispatch, debug, ensure,
+	decl_module, decl_storage, decl_event, decl_error, dispatch, debug, ensure, transactional,
 	traits::{Currency, EnsureOrigin, ReservableCurrency, OnUnbalanced, Get, ExistenceRequirement::{KeepAlive}},
+	weights::Weight,
 };
-use sp_runtime::{ModuleId, traits::{ Hash, AccountIdConversion}};
+use sp_runtime::{ModuleId, RuntimeDebug, traits::{ Hash, AccountIdConversion, Bounded}};
 use frame_support::codec::{Encode, Decode};
 use frame_system::{ensure_signed};
 use sp_std::{vec, vec::Vec, convert::{TryInto}};
 
+pub use weights::WeightInfo;
+
+mod migrations;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[cfg(test)]
 mod mock;
 
@@ -39,6 +48,34 @@ pub struct Round {
 	pub total_tax: u128,
 }
 
+/// Calls which the admin can pause when a bug is found
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CallCategory {
+	// every call below
+	All,
+	// donating to a round and registering projects
+	Deposits,
+	// voting for projects
+	Votes,
+	// ending a round and withdrawing grants, which pay out the grants
+	Transfers,
+}
+
+/// Storage layout version, used by `on_runtime_upgrade` to decide which migrations to run
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	// projects are not counted
+	V1_0_0,
+	// `ProjectCount` tracks the projects of each round
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
 // TODO: This is designed for rpc return, need to investigate feasibility, use tuple instead
 // #[derive(Encode, Decode, Default, Clone, PartialEq)]
 // pub struct RankingProject<ProjectHash> {
@@ -85,7 +122,14 @@ pub trait Config: frame_system::Trait {
 	/// The maximum length of project name
 	type NameMaxLength: Get<usize>;
 
-	
+	/// The maximum number of projects registered in a round, which bounds the payouts of `end_round`
+	type MaxProjectsPerRound: Get<u32>;
+
+	/// The deposit reserved from the owner of a project until its round ends
+	type ProjectDeposit: Get<BalanceOf<Self>>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
 }
 
 // The pallet's runtime storage items.
@@ -96,10 +140,18 @@ decl_storage! {
 	trait Store for Module<T: Config> as QuadraticFunding {
 		// Learn more about declaring storage items:
 		// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
+		// Layout of the storage, new chains start with the latest one
+		StorageVersion get(fn storage_version) build(|_| Releases::V2_0_0): Releases;
 		// Map, each round start with an id => bool 
 		Rounds get(fn rounds): map hasher(blake2_128_concat) u32 => Round;
 		Projects get(fn projects): double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::Hash => ProjectOf<T>;
+		// number of projects registered in a round, the witness of `end_round`
+		ProjectCount get(fn project_count): map hasher(blake2_128_concat) u32 => u32;
+		// deposit reserved from the owner of a project, returned when its round ends
+		ProjectDeposits get(fn project_deposit): double_map hasher(blake2_128_concat) u32, hasher(blake2_128_concat) T::Hash => BalanceOf<T>;
 		ProjectVotes: double_map hasher(blake2_128_concat) T::Hash, hasher(blake2_128_concat) T::AccountId => u128;
+		// paused call categories => the block the pause ends at, `BlockNumber::max_value()` if it does not expire
+		Pauses get(fn pauses): map hasher(blake2_128_concat) CallCategory => Option<T::BlockNumber>;
 	}
 	add_extra_genesis {
 		build(|_config| {
@@ -115,7 +167,8 @@ decl_storage! {
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId, Hash =  <T as frame_system::Trait>::Hash, {
+	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId, Hash =  <T as frame_system::Trait>::Hash,
+	        BlockNumber = <T as frame_system::Trait>::BlockNumber {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [project_hash, who]
 		ProjectRegistered(Hash, AccountId),
@@ -129,6 +182,15 @@ decl_event!(
 		RoundEnded(u32),
 		/// parameters. [round_id, who, amount]
 		DonateSucceed(u32, AccountId, u128),
+		/// parameters. [category, until]
+		CallsPaused(CallCategory, Option<BlockNumber>),
+		/// parameters. [category]
+		CallsUnpaused(CallCategory),
+		/// The grants could not be paid out when the round ended, the owner can withdraw them.
+		/// parameters. [round_id, project_hash, grants]
+		GrantUnpaid(u32, Hash, u128),
+		/// parameters. [round_id, project_hash, who, amount]
+		GrantWithdrawn(u32, Hash, AccountId, u128),
 	}
 );
 
@@ -149,6 +211,12 @@ decl_error! {
 		RoundNotExist,
 		RoundHasEnded,
 		DuplicateRound,
+		InvalidProjectCount,
+		TooManyProjects,
+		CallsArePaused,
+		RoundNotEnded,
+		NotProjectOwner,
+		NothingToWithdraw,
 	}
 }
 
@@ -162,16 +230,25 @@ decl_module! {
 
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v2::<T>()
+		}
+
 		const UnitOfVote: u128 = T::UnitOfVote::get();
 		const NumberOfUnitPerVote: u128 = T::NumberOfUnitPerVote::get();
 		const FeeRatioPerVote: u128 = T::FeeRatioPerVote::get();
 		const NameMinLength: u32 = T::NameMinLength::get() as u32;
 		const NameMaxLength: u32 = T::NameMaxLength::get() as u32;
+		const MaxProjectsPerRound: u32 = T::MaxProjectsPerRound::get();
+		const ProjectDeposit: BalanceOf<T> = T::ProjectDeposit::get();
 
 		/// A round gets sponsored, this will transfer from sponsor's account to our internal account with the amount to be sponsored
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
+		#[weight = T::WeightInfo::donate()]
+		#[transactional]
 		pub fn donate(origin, round_id: u32, #[compact] amount: BalanceOf<T>) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
 			ensure!(Rounds::contains_key(&round_id), Error::<T>::RoundNotExist);
 			let round = Rounds::get(round_id);
 			ensure!(true == round.ongoing, Error::<T>::RoundHasEnded);
@@ -180,7 +257,7 @@ decl_module! {
 			let amount_number = Self::balance_to_u128(amount);
 			let fee_number = T::FeeRatioPerVote::get().checked_mul(amount_number / T::NumberOfUnitPerVote::get()).unwrap();
 			ensure!(amount_number > min_unit_number, Error::<T>::DonationTooSmall);
-			let _ = T::Currency::transfer(&who, &Self::account_id(), amount, KeepAlive);
+			T::Currency::transfer(&who, &Self::account_id(), amount, KeepAlive)?;
 			// update the round
 			Rounds::mutate(round_id, |rnd| {
 				let ptsp = rnd.pre_tax_support_pool;
@@ -195,7 +272,7 @@ decl_module! {
 		}
 
 		/// Create a new round, make sure to use a fresh index, any used index is not allowed, even those ended
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
+		#[weight = T::WeightInfo::start_round()]
 		pub fn start_round(origin, round_id: u32) -> dispatch::DispatchResult {
 			// Only amdin can control the round 
 			T::AdminOrigin::ensure_origin(origin)?;
@@ -213,11 +290,18 @@ decl_module! {
 		}
 
 		/// End an `ongoing` round and distribute the funds in sponsor pool, any invalid index or round status will cause errors
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-		pub fn end_round(origin, round_id: u32) -> dispatch::DispatchResult {
+		/// `project_count` must be no less than the number of projects in the round, it bounds the weight of the payouts.
+		/// The deposits of the projects are returned to their owners.
+		/// A payout which fails, e.g. a grant below the existential deposit to an account which does not exist, does not hold up
+		/// the others, its owner can withdraw it with `withdraw_grant`
+		#[weight = T::WeightInfo::end_round(*project_count)]
+		#[transactional]
+		pub fn end_round(origin, round_id: u32, project_count: u32) -> dispatch::DispatchResult {
 			// Only amdin can control the round 
 			T::AdminOrigin::ensure_origin(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
 			ensure!(Rounds::contains_key(&round_id), Error::<T>::RoundNotExist);
+			ensure!(project_count >= ProjectCount::get(round_id), Error::<T>::InvalidProjectCount);
 			let mut round = Rounds::get(round_id);
 			ensure!(true == round.ongoing, Error::<T>::RoundHasEnded);
 			let area = round.total_support_area;
@@ -231,12 +315,12 @@ decl_module! {
 				}
 				debug::info!("Hash: {:?}, Total votes: {:?}, Grants: {:?}", hash, project.total_votes, project.grants);
 				// reckon the final grants
-				let _ = T::Currency::transfer(
-					&Self::account_id(),
-					&project.owner,
-					Self::u128_to_balance(project.grants),
-					KeepAlive
-				);
+				match Self::pay_grant(&project.owner, project.grants) {
+					Ok(()) => project.withdrew = project.grants,
+					Err(_) => Self::deposit_event(RawEvent::GrantUnpaid(round_id, hash, project.grants)),
+				}
+				T::Currency::unreserve(&project.owner, ProjectDeposits::<T>::take(round_id, hash));
+				Projects::<T>::insert(round_id, hash, project);
 			}
 			round.ongoing = false;
 			Rounds::insert(round_id, round);
@@ -244,13 +328,41 @@ decl_module! {
 			Ok(())
 		}
 
-		/// Register a project in an ongoing round, so that it can be voted
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
+		/// The owner of a project withdraws the grants which could not be paid out when the round ended
+		#[weight = T::WeightInfo::withdraw_grant()]
+		#[transactional]
+		pub fn withdraw_grant(origin, round_id: u32, hash: T::Hash) -> dispatch::DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Transfers)?;
+			ensure!(Rounds::contains_key(&round_id), Error::<T>::RoundNotExist);
+			ensure!(!Rounds::get(round_id).ongoing, Error::<T>::RoundNotEnded);
+			ensure!(Projects::<T>::contains_key(&round_id, &hash), Error::<T>::ProjectNotExist);
+			let mut project = Projects::<T>::get(round_id, hash);
+			ensure!(who == project.owner, Error::<T>::NotProjectOwner);
+			let amount = project.grants.saturating_sub(project.withdrew);
+			ensure!(amount > 0, Error::<T>::NothingToWithdraw);
+			Self::pay_grant(&who, amount)?;
+			project.withdrew = project.grants;
+			Projects::<T>::insert(round_id, hash, project);
+			Self::deposit_event(RawEvent::GrantWithdrawn(round_id, hash, who, amount));
+			Ok(())
+		}
+
+		/// Register a project in an ongoing round, so that it can be voted, `ProjectDeposit` is reserved until the round ends
+		#[weight = T::WeightInfo::register_project()]
+		#[transactional]
 		pub fn register_project(origin, round_id: u32, hash: T::Hash, name: Vec<u8>) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Deposits)?;
 			ensure!(name.len() >= T::NameMinLength::get(), Error::<T>::ProjectNameTooShort);
 			ensure!(name.len() <= T::NameMaxLength::get(), Error::<T>::ProjectNameTooLong);
+			ensure!(Rounds::contains_key(&round_id), Error::<T>::RoundNotExist);
+			ensure!(Rounds::get(round_id).ongoing, Error::<T>::RoundHasEnded);
 			ensure!(!Projects::<T>::contains_key(&round_id, &hash), Error::<T>::DuplicateProject);
+			ensure!(ProjectCount::get(round_id) < T::MaxProjectsPerRound::get(), Error::<T>::TooManyProjects);
+			let deposit = T::ProjectDeposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			ProjectDeposits::<T>::insert(round_id, hash, deposit);
 			let project = Project {
 				total_votes: 0,
 				grants: 0,
@@ -260,14 +372,17 @@ decl_module! {
 				owner: who.clone(),
 			};
 			Projects::<T>::insert(round_id, hash, project);
+			ProjectCount::mutate(round_id, |count| *count += 1);
 			Self::deposit_event(RawEvent::ProjectRegistered(hash, who));
 			Ok(())
 		}
 
 		/// Vote to a project, this function will transfer corresponding amount of token per your input ballot
-		#[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
+		#[weight = T::WeightInfo::vote()]
+		#[transactional]
 		pub fn vote(origin, round_id: u32, hash: T::Hash, ballot: u128) -> dispatch::DispatchResult {
 			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(CallCategory::Votes)?;
 			ensure!(Projects::<T>::contains_key(&round_id, &hash), Error::<T>::ProjectNotExist);
 			ensure!(ballot > 0, Error::<T>::InvalidBallot);
 			// check whether this round still ongoing
@@ -282,7 +397,7 @@ decl_module! {
 			let amount = Self::cal_amount(cost, false);
 			let fee = Self::cal_amount(cost, true);
 			// transfer first, update last, as transfer will ensure the free balance is enough
-			let _ = T::Currency::transfer(&who, &Self::account_id(), Self::u128_to_balance(amount), KeepAlive);
+			T::Currency::transfer(&who, &Self::account_id(), Self::u128_to_balance(amount), KeepAlive)?;
 
 			// update the project and corresponding round
 			ProjectVotes::<T>::insert(vote_hash, &who, ballot+voted);
@@ -303,6 +418,24 @@ decl_module! {
 			Self::deposit_event(RawEvent::VoteSucceed(hash, who, ballot));
 			Ok(())
 		}
+
+		/// Pause a category of calls until the block `until`, or until `unpause` if it is none
+		#[weight = T::WeightInfo::pause()]
+		pub fn pause(origin, category: CallCategory, until: Option<T::BlockNumber>) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Pauses::<T>::insert(category, until.unwrap_or_else(T::BlockNumber::max_value));
+			Self::deposit_event(RawEvent::CallsPaused(category, until));
+			Ok(())
+		}
+
+		/// Lift the pause of a category of calls before it expires
+		#[weight = T::WeightInfo::unpause()]
+		pub fn unpause(origin, category: CallCategory) -> dispatch::DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			Pauses::<T>::remove(category);
+			Self::deposit_event(RawEvent::CallsUnpaused(category));
+			Ok(())
+		}
 	}
 }
 
@@ -317,6 +450,24 @@ impl<T: Config> Module<T> {
 		T::ModuleId::get().into_account()
 	}
 
+	/// Whether the calls of `category` are paused, by their own pause or by pausing all calls
+	pub fn is_paused(category: CallCategory) -> bool {
+		let now = frame_system::Module::<T>::block_number();
+		[CallCategory::All, category].iter().any(|category| Pauses::<T>::get(category).map_or(false, |until| now < until))
+	}
+
+	/// Fails with `CallsArePaused` while `category` is paused
+	pub fn ensure_not_paused(category: CallCategory) -> dispatch::DispatchResult {
+		ensure!(!Self::is_paused(category), Error::<T>::CallsArePaused);
+		Ok(())
+	}
+
+	/// Pay grants out of the internal account, which is kept alive, nothing is moved if it fails
+	#[transactional]
+	fn pay_grant(owner: &T::AccountId, grants: u128) -> dispatch::DispatchResult {
+		T::Currency::transfer(&Self::account_id(), owner, Self::u128_to_balance(grants), KeepAlive)
+	}
+
 	pub fn cal_cost(voted: u128, ballot: u128) -> u128 {
 		let mut points = ballot.checked_mul(ballot.checked_add(1).unwrap()).unwrap() / 2; 
 		points = points.checked_add(ballot.checked_mul(voted).unwrap()).unwrap();
@@ -0,0 +1,19 @@
+/// Storage migrations of the pallet, each one runs once and bumps `StorageVersion`
+use super::*;
+use frame_support::{StorageValue, StorageMap, IterableStorageDoubleMap};
+
+/// Count the projects of every round, so that `end_round` can check its witness
+pub fn migrate_to_v2<T: Config>() -> Weight {
+	if StorageVersion::get() != Releases::V1_0_0 {
+		return 0;
+	}
+
+	let mut counted: Weight = 0;
+	for (round_id, _, _) in Projects::<T>::iter() {
+		ProjectCount::mutate(round_id, |count| *count += 1);
+		counted += 1;
+	}
+	StorageVersion::put(Releases::V2_0_0);
+
+	T::DbWeight::get().reads_writes(counted * 2 + 1, counted + 1)
+}
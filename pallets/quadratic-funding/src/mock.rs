@@ -44,6 +44,8 @@ parameter_types! {
 	pub const QuadraticFundingModuleId: ModuleId = ModuleId(*b"py/quafd");
 	pub const NameMinLength: usize = 3;
 	pub const NameMaxLength: usize = 32;
+	pub const MaxProjectsPerRound: u32 = 3;
+	pub const ProjectDeposit: u64 = 10;
 }
 
 impl system::Trait for Test {
@@ -114,6 +116,14 @@ impl Config for Test {
 
 	// The maximum length of project name
 	type NameMaxLength = NameMaxLength;
+
+	// The maximum number of projects in a round
+	type MaxProjectsPerRound = MaxProjectsPerRound;
+
+	// The deposit reserved from the owner of a project until its round ends
+	type ProjectDeposit = ProjectDeposit;
+
+	type WeightInfo = ();
 }
 
 pub type System = frame_system::Module<Test>;
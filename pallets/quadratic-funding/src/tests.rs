@@ -1,8 +1,8 @@
 use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use frame_support::{assert_ok, assert_noop, StorageMap, StorageValue, traits::{OnRuntimeUpgrade, Currency, ReservableCurrency}};
 use sp_core::H256;
 use sp_runtime::traits::BadOrigin;
-use super::RawEvent;
+use super::{RawEvent, Releases, ProjectCount, StorageVersion, CallCategory};
 
 /// generate a Hash for indexing project
 fn get_hash(value: u128) -> H256 {
@@ -10,7 +10,7 @@ fn get_hash(value: u128) -> H256 {
 	H256::from_slice(&slices.repeat(2))
 }
 
-fn last_event() -> RawEvent<u64, H256> {
+fn last_event() -> RawEvent<u64, H256, u64> {
 	System::events().into_iter().map(|r| r.event)
 		.filter_map(|e| {
 			if let Event::quadratic_funding(inner) = e { Some(inner) } else { None }
@@ -27,8 +27,8 @@ fn round_control_works() {
 		assert_noop!(QuadraticFunding::start_round(Origin::signed(1), round_id), BadOrigin);
 		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
 
-		assert_noop!(QuadraticFunding::end_round(Origin::signed(1), round_id), BadOrigin);
-		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id));
+		assert_noop!(QuadraticFunding::end_round(Origin::signed(1), round_id, 0), BadOrigin);
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 0));
 	});
 }
 
@@ -42,19 +42,37 @@ fn register_project_works() {
 		let round_id = 1;
 		// Dispatch a signed extrinsic.
 		// should start round first
+		assert_noop!(
+			QuadraticFunding::register_project(Origin::signed(1), round_id, hash, project_name.clone()),
+			Error::<Test>::RoundNotExist
+		);
 		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
 		assert_ok!(QuadraticFunding::register_project(Origin::signed(1), round_id, hash, project_name.clone()));
 		
 		// Read pallet storage and assert an expected result.
 		// positive case
 		assert_eq!(QuadraticFunding::projects(round_id, hash).name, project_name);
+		// the deposit of 10 is reserved until the round ends
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(Balances::free_balance(1), 1990);
+		assert_eq!(QuadraticFunding::project_deposit(round_id, hash), 10);
 		// negative case
 		assert_noop!(
-			QuadraticFunding::register_project(Origin::signed(1), round_id, hash, project_name),
+			QuadraticFunding::register_project(Origin::signed(1), round_id, hash, project_name.clone()),
 			Error::<Test>::DuplicateProject
 		);
 
 		assert_eq!(Balances::free_balance(0), 1000);
+
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 1));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 2000);
+		assert_eq!(QuadraticFunding::project_deposit(round_id, hash), 0);
+		// no more projects once the round has ended
+		assert_noop!(
+			QuadraticFunding::register_project(Origin::signed(2), round_id, get_hash(2), project_name),
+			Error::<Test>::RoundHasEnded
+		);
 		// Deprecated! This method has been changed to rpc
 		// assert_ok!(QuadraticFunding::vote_cost(Origin::signed(1), round_id, hash, 1));
 		// assert_eq!(last_event(), RawEvent::VoteCost(hash,1));
@@ -98,10 +116,10 @@ fn vote_without_fund_works() {
 			let vote = 3;
 			let expected_cost:u64 = vote * (vote + 1) / 2 * 100;
 			assert_ok!(QuadraticFunding::vote(Origin::signed(i), round_id, hash, vote.into()));
-			// We initialize the balance sequentially, each one got 1000*(i+1) pico
-			assert_eq!(Balances::free_balance(i), 1000*(i+1) - expected_cost);
+			// We initialize the balance sequentially, each one got 1000*(i+1) pico, 10 of it is reserved as the project deposit
+			assert_eq!(Balances::free_balance(i), 1000*(i+1) - 10 - expected_cost);
 		}
-		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id));
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 3));
 		// no support area means no fund expense
 		assert_eq!(QuadraticFunding::rounds(round_id).total_support_area, 0);
 	});
@@ -135,4 +153,171 @@ fn vote_with_fund_works() {
 		assert_eq!(QuadraticFunding::rounds(round_id).total_support_area, 18);
 		
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn end_round_with_wrong_witness_failed() {
+	new_test_ext().execute_with(|| {
+		let round_id = 1;
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
+		for i in 1..4 {
+			assert_ok!(QuadraticFunding::register_project(Origin::signed(i), round_id, get_hash(i.into()), b"name".to_vec()));
+		}
+		assert_eq!(QuadraticFunding::project_count(round_id), 3);
+		// the witness must cover every project of the round
+		assert_noop!(
+			QuadraticFunding::end_round(Origin::root(), round_id, 2),
+			Error::<Test>::InvalidProjectCount
+		);
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 3));
+		assert_eq!(QuadraticFunding::rounds(round_id).ongoing, false);
+	});
+}
+
+#[test]
+fn register_project_over_limit_failed() {
+	new_test_ext().execute_with(|| {
+		let round_id = 1;
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
+		for i in 1..4 {
+			assert_ok!(QuadraticFunding::register_project(Origin::signed(i), round_id, get_hash(i.into()), b"name".to_vec()));
+		}
+		// at most 3 projects per round in the mock
+		assert_noop!(
+			QuadraticFunding::register_project(Origin::signed(0), round_id, get_hash(4), b"name".to_vec()),
+			Error::<Test>::TooManyProjects
+		);
+		// other rounds are counted on their own
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id + 1));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(0), round_id + 1, get_hash(4), b"name".to_vec()));
+	});
+}
+
+#[test]
+fn migrate_project_count_works() {
+	new_test_ext().execute_with(|| {
+		// projects registered before `ProjectCount` existed
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), 1));
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), 2));
+		for i in 1..4 {
+			assert_ok!(QuadraticFunding::register_project(Origin::signed(i), 1, get_hash(i.into()), b"name".to_vec()));
+		}
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(1), 2, get_hash(1), b"name".to_vec()));
+		ProjectCount::remove(1);
+		ProjectCount::remove(2);
+		StorageVersion::put(Releases::V1_0_0);
+
+		QuadraticFunding::on_runtime_upgrade();
+		assert_eq!(QuadraticFunding::storage_version(), Releases::V2_0_0);
+		assert_eq!(QuadraticFunding::project_count(1), 3);
+		assert_eq!(QuadraticFunding::project_count(2), 1);
+
+		// runs only once
+		QuadraticFunding::on_runtime_upgrade();
+		assert_eq!(QuadraticFunding::project_count(1), 3);
+	});
+}
+
+#[test]
+fn pause_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let round_id = 1;
+		let hash = get_hash(1);
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(1), round_id, hash, b"name".to_vec()));
+		assert_noop!(QuadraticFunding::pause(Origin::signed(1), CallCategory::All, None), BadOrigin);
+
+		// votes are paused until block 5
+		assert_ok!(QuadraticFunding::pause(Origin::root(), CallCategory::Votes, Some(5)));
+		assert_eq!(last_event(), RawEvent::CallsPaused(CallCategory::Votes, Some(5)));
+		assert_noop!(QuadraticFunding::vote(Origin::signed(2), round_id, hash, 1), Error::<Test>::CallsArePaused);
+		assert_ok!(QuadraticFunding::donate(Origin::signed(0), round_id, 500));
+		System::set_block_number(5);
+		assert_ok!(QuadraticFunding::vote(Origin::signed(2), round_id, hash, 1));
+
+		// registering projects is paused with the deposits
+		assert_ok!(QuadraticFunding::pause(Origin::root(), CallCategory::Deposits, None));
+		assert_noop!(QuadraticFunding::register_project(Origin::signed(2), round_id, get_hash(2), b"name".to_vec()), Error::<Test>::CallsArePaused);
+		assert_ok!(QuadraticFunding::unpause(Origin::root(), CallCategory::Deposits));
+
+		// everything is paused until it is lifted
+		assert_ok!(QuadraticFunding::pause(Origin::root(), CallCategory::All, None));
+		assert_noop!(QuadraticFunding::donate(Origin::signed(0), round_id, 100), Error::<Test>::CallsArePaused);
+		assert_noop!(QuadraticFunding::register_project(Origin::signed(2), round_id, get_hash(2), b"name".to_vec()), Error::<Test>::CallsArePaused);
+		assert_noop!(QuadraticFunding::end_round(Origin::root(), round_id, 1), Error::<Test>::CallsArePaused);
+		assert_ok!(QuadraticFunding::unpause(Origin::root(), CallCategory::All));
+		assert_eq!(last_event(), RawEvent::CallsUnpaused(CallCategory::All));
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 1));
+	});
+}
+
+#[test]
+fn payments_without_balance_fail() {
+	new_test_ext().execute_with(|| {
+		let round_id = 1;
+		let hash = get_hash(1);
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
+		assert_ok!(QuadraticFunding::register_project(Origin::signed(1), round_id, hash, b"name".to_vec()));
+
+		// more than the free balance, or all of it which would reap the account
+		assert_noop!(
+			QuadraticFunding::donate(Origin::signed(0), round_id, 2000),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_noop!(
+			QuadraticFunding::donate(Origin::signed(0), round_id, 1000),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::KeepAlive
+		);
+		assert_eq!(QuadraticFunding::rounds(round_id).pre_tax_support_pool, 0);
+
+		// 4 ballots cost 1000, 5 ballots 1500
+		assert_noop!(
+			QuadraticFunding::vote(Origin::signed(0), round_id, hash, 5),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_noop!(
+			QuadraticFunding::vote(Origin::signed(0), round_id, hash, 4),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::KeepAlive
+		);
+		assert_eq!(QuadraticFunding::projects(round_id, hash).total_votes, 0);
+	});
+}
+
+#[test]
+fn end_round_leaves_failed_payouts_to_withdraw() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let round_id = 1;
+		assert_ok!(QuadraticFunding::start_round(Origin::root(), round_id));
+		// one ballot each, grants of 95 after the fee
+		for i in 1..3 {
+			assert_ok!(QuadraticFunding::register_project(Origin::signed(i), round_id, get_hash(i.into()), b"name".to_vec()));
+			assert_ok!(QuadraticFunding::vote(Origin::signed(3), round_id, get_hash(i.into()), 1));
+		}
+		assert_noop!(QuadraticFunding::withdraw_grant(Origin::signed(1), round_id, get_hash(1)), Error::<Test>::RoundNotEnded);
+
+		// the pallet account can pay the second grant only by dropping below the existential deposit
+		let _ = Balances::make_free_balance_be(&QuadraticFunding::account_id(), 190);
+		assert_ok!(QuadraticFunding::end_round(Origin::root(), round_id, 2));
+		assert!(!QuadraticFunding::rounds(round_id).ongoing);
+		assert_eq!(Balances::free_balance(1) + Balances::free_balance(2), 2000 + 3000 + 95);
+		let unpaid: Vec<u64> = (1..3).filter(|i| QuadraticFunding::projects(round_id, get_hash((*i).into())).withdrew == 0).collect();
+		assert_eq!(unpaid.len(), 1);
+		let (owner, hash) = (unpaid[0], get_hash(unpaid[0].into()));
+		assert!(System::events().into_iter().any(|r| r.event == Event::quadratic_funding(RawEvent::GrantUnpaid(round_id, hash, 95))));
+
+		// the owner withdraws it once the pallet account can pay
+		assert_noop!(QuadraticFunding::withdraw_grant(Origin::signed(3 - owner), round_id, hash), Error::<Test>::NotProjectOwner);
+		assert_noop!(
+			QuadraticFunding::withdraw_grant(Origin::signed(owner), round_id, hash),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::KeepAlive
+		);
+		let _ = Balances::make_free_balance_be(&QuadraticFunding::account_id(), 96);
+		assert_ok!(QuadraticFunding::withdraw_grant(Origin::signed(owner), round_id, hash));
+		assert_eq!(last_event(), RawEvent::GrantWithdrawn(round_id, hash, owner, 95));
+		assert_eq!(Balances::free_balance(1), 2095);
+		assert_eq!(Balances::free_balance(2), 3095);
+		assert_noop!(QuadraticFunding::withdraw_grant(Origin::signed(owner), round_id, hash), Error::<Test>::NothingToWithdraw);
+	});
+}
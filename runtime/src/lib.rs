@@ -10,7 +10,7 @@ use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-	transaction_validity::{TransactionValidity, TransactionSource}, ModuleId,
+	transaction_validity::{TransactionValidity, TransactionSource}, ModuleId, DispatchError,
 };
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor, Saturating,
@@ -32,7 +32,7 @@ pub use sp_runtime::{Permill, Perbill};
 use frame_system::{EnsureRoot};
 pub use frame_support::{
 	construct_runtime, parameter_types, StorageValue, 
-	traits::{KeyOwnerProofSystem, Randomness},
+	traits::{KeyOwnerProofSystem, Randomness, Filter},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -59,6 +59,17 @@ pub type AccountIndex = u32;
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Signed version of Balance, used by the token pallets.
+pub type Amount = i128;
+
+/// Identifier of a token, the native token is `NATIVE_CURRENCY_ID`.
+pub type CurrencyId = u32;
+
+/// The native token.
+pub const NATIVE_CURRENCY_ID: CurrencyId = 0;
+/// A stablecoin for DAO tributes and payments.
+pub const USD_CURRENCY_ID: CurrencyId = 1;
+
 /// Index of a transaction in the chain.
 pub type Index = u32;
 
@@ -273,6 +284,9 @@ parameter_types! {
 	pub const QuadraticFundingModuleId: ModuleId = ModuleId(*b"py/quafd");
 	pub const NameMinLength: usize = 3;
 	pub const NameMaxLength: usize = 32;
+	pub const MaxProjectsPerRound: u32 = 1000;
+	// The deposit reserved for each registered project, 1000 units of token
+	pub const ProjectDeposit: Balance = 1_000_000_000_000;
 }
 
 /// Configure the template pallet in pallets/template.
@@ -303,8 +317,37 @@ impl pallet_quadratic_funding::Config for Runtime {
 	// The maximum length of project name
 	type NameMaxLength = NameMaxLength;
 
+	// The maximum number of projects in a round
+	type MaxProjectsPerRound = MaxProjectsPerRound;
+
+	// The deposit reserved from the owner of a project until its round ends
+	type ProjectDeposit = ProjectDeposit;
+
 	// Origin who can control the round
 	type AdminOrigin = EnsureRoot<AccountId>;
+
+	type WeightInfo = pallet_quadratic_funding::weights::SubstrateWeight<Runtime>;
+}
+
+impl orml_tokens::Trait for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type OnReceived = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const GetNativeCurrencyId: CurrencyId = NATIVE_CURRENCY_ID;
+}
+
+impl orml_currencies::Trait for Runtime {
+	type Event = Event;
+	type MultiCurrency = Tokens;
+	type NativeCurrency = orml_currencies::BasicCurrencyAdapter<Runtime, Balances, Amount, BlockNumber>;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type WeightInfo = ();
 }
 
 parameter_types! {
@@ -317,18 +360,41 @@ parameter_types! {
     pub const MaxGracePeriodLength: u128 = 1000_000_000_000_000_000; // maximum length of grace period
     pub const MaxDilutionBound: u128 = 1000_000_000_000_000_000; // maximum dilution bound
     pub const MaxShares: u128 = 1000_000_000_000_000_000; // maximum number of shares that can be minted
+    pub const MaxTokens: u32 = 50; // maximum number of whitelisted tokens of a DAO
+	/// Processing ready proposals takes up to 5% of the maximum block weight.
+	pub AutoProcessBudget: Weight = Perbill::from_percent(5) * MaximumBlockWeight::get();
+	/// Processing rewards of auto-processed proposals go to the guild bank of each DAO.
+	pub const AutoProcessReward: Option<AccountId> = None;
+	/// The call of an action proposal can take up to a tenth of a block.
+	pub MaxActionWeight: Weight = Perbill::from_percent(10) * MaximumBlockWeight::get();
+}
 
+/// Calls which action proposals of the DAOs may dispatch: transfers and taking part in funding rounds.
+pub struct ActionCallFilter;
+impl Filter<Call> for ActionCallFilter {
+	fn filter(call: &Call) -> bool {
+		matches!(call,
+			Call::Balances(pallet_balances::Call::transfer(..)) |
+			Call::Balances(pallet_balances::Call::transfer_keep_alive(..)) |
+			Call::Currencies(orml_currencies::Call::transfer(..)) |
+			Call::Currencies(orml_currencies::Call::transfer_native_currency(..)) |
+			Call::QuadraticFunding(pallet_quadratic_funding::Call::donate(..)) |
+			Call::QuadraticFunding(pallet_quadratic_funding::Call::register_project(..)) |
+			Call::QuadraticFunding(pallet_quadratic_funding::Call::vote(..))
+		)
+	}
 }
 
 /// Configure the template pallet in pallets/template.
 impl pallet_moloch_v2::Config for Runtime {
 	type ModuleId = MolochV2ModuleId;
-    // The Balances pallet implements the ReservableCurrency trait.
-    // https://substrate.dev/rustdocs/v2.0.0/pallet_balances/index.html#implementations-2
-    type Currency = pallet_balances::Module<Runtime>;
+	type CurrencyId = CurrencyId;
+    // The native token goes through Balances, the other tokens through orml_tokens.
+    type Currency = Currencies;
+	type NativeCurrencyId = GetNativeCurrencyId;
 
-    // No action is taken when deposits are forfeited.
-    type Slashed = ();
+	// period_duration of a DAO is in seconds, periods could be counted in blocks with `BlockNumberClock`
+	type Clock = pallet_moloch_v2::clock::TimestampClock<Runtime>;
 
     // The ubiquitous event type.
     type Event = Event;
@@ -348,6 +414,23 @@ impl pallet_moloch_v2::Config for Runtime {
 	// maximum number of shares
 	type MaxShares = MaxShares;
 
+	// maximum number of whitelisted tokens
+	type MaxTokens = MaxTokens;
+
+	// proposals processed in on_initialize and where their rewards go
+	type AutoProcessBudget = AutoProcessBudget;
+	type AutoProcessReward = AutoProcessReward;
+
+	// calls of action proposals
+	type Call = Call;
+	type CallFilter = ActionCallFilter;
+	type MaxActionWeight = MaxActionWeight;
+
+	// relayed votes are signed like extrinsics
+	type OffchainSignature = Signature;
+	type SigningPublicKey = <Signature as Verify>::Signer;
+
+	type WeightInfo = pallet_moloch_v2::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -365,9 +448,11 @@ construct_runtime!(
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
+		Tokens: orml_tokens::{Module, Storage, Event<T>, Config<T>},
+		Currencies: orml_currencies::{Module, Call, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
 		QuadraticFunding: pallet_quadratic_funding::{Module, Call, Storage, Event<T>},
-		MolochV2: pallet_moloch_v2::{Module, Call, Storage, Event<T>},
+		MolochV2: pallet_moloch_v2::{Module, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -541,6 +626,51 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_moloch_v2_runtime_api::MolochV2Api<Block, AccountId, CurrencyId> for Runtime {
+		fn user_token_balance(dao_id: u32, who: AccountId, token: CurrencyId) -> u128 {
+			MolochV2::user_token_balances((dao_id, who), token)
+		}
+		fn user_token_balances(dao_id: u32, who: AccountId) -> Vec<(CurrencyId, u128)> {
+			MolochV2::user_balances(dao_id, who)
+		}
+		fn member(dao_id: u32, who: AccountId) -> Option<pallet_moloch_v2::Member<AccountId>> {
+			MolochV2::member(dao_id, who)
+		}
+		fn proposals(dao_id: u32, start: u128, count: u32) -> Vec<(u128, pallet_moloch_v2::Proposal<AccountId, CurrencyId>, pallet_moloch_v2::ProposalStatus)> {
+			MolochV2::proposals_page(dao_id, start, count)
+		}
+		fn proposal_queue(dao_id: u32) -> Vec<u128> {
+			MolochV2::proposal_queue(dao_id)
+		}
+		fn current_period(dao_id: u32) -> Option<u128> {
+			MolochV2::current_period(dao_id)
+		}
+		fn member_vote(dao_id: u32, proposal_id: u128, who: AccountId) -> Option<u8> {
+			MolochV2::member_vote(dao_id, proposal_id, who)
+		}
+		fn guild_bank_balances(dao_id: u32) -> Vec<(CurrencyId, u128)> {
+			MolochV2::guild_bank_balances(dao_id)
+		}
+		fn preview_ragequit(dao_id: u32, who: AccountId, shares_to_burn: u128, loot_to_burn: u128) -> Result<Vec<(CurrencyId, u128)>, DispatchError> {
+			MolochV2::ragequit_payout(dao_id, &who, shares_to_burn, loot_to_burn)
+		}
+		fn preview_outcome(dao_id: u32, proposal_id: u128) -> Result<Option<pallet_moloch_v2::FailureReason>, DispatchError> {
+			MolochV2::preview_outcome(dao_id, proposal_id)
+		}
+		fn preview_process(dao_id: u32, proposal_index: u128) -> Result<pallet_moloch_v2::ProcessPreview<CurrencyId>, DispatchError> {
+			MolochV2::preview_process(dao_id, proposal_index)
+		}
+		fn shares_at(dao_id: u32, who: AccountId, period: u128) -> u128 {
+			MolochV2::shares_at(dao_id, &who, period)
+		}
+		fn total_shares_at(dao_id: u32, period: u128) -> u128 {
+			MolochV2::total_shares_at(dao_id, period)
+		}
+		fn vote_nonce(dao_id: u32, who: AccountId) -> u64 {
+			MolochV2::vote_nonce(dao_id, who)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -575,6 +705,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_moloch_v2, MolochV2);
+			add_benchmark!(params, batches, pallet_quadratic_funding, QuadraticFunding);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+
+set -e
+
+echo "*** Benchmarking the DAO pallets ***"
+
+cd "$(dirname "$0")/.."
+
+cargo +nightly-2020-10-06 build --release --manifest-path node/Cargo.toml --features runtime-benchmarks
+
+for pallet in moloch-v2 quadratic-funding; do
+    ./target/release/dora-moloch benchmark \
+        --chain dev \
+        --execution wasm \
+        --wasm-execution compiled \
+        --pallet "pallet_${pallet//-/_}" \
+        --extrinsic '*' \
+        --steps 50 \
+        --repeat 20 \
+        --output "./pallets/${pallet}/src/weights.rs"
+done